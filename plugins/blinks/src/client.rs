@@ -0,0 +1,389 @@
+//! HTTP side of the Solana Actions spec: URL resolution, the GET metadata
+//! request and the POST that returns a transaction.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use solana_actions_core::error::{ensure_success, ActionError};
+use solana_sdk::pubkey::Pubkey;
+use url::Url;
+
+use crate::security::BlinkGuard;
+
+/// Scheme prefix of an explicit action URL, e.g. `solana-action:https%3A%2F%2F...`.
+pub const ACTION_SCHEME: &str = "solana-action:";
+/// Solana Pay transaction requests use the same GET/POST exchange.
+const SOLANA_PAY_SCHEME: &str = "solana:";
+const PROVIDER: &str = "Blink";
+
+/// Response to the GET on an action URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionGetResponse {
+    /// `action`, or `completed` at the end of a chain; absent in older servers.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub icon: String,
+    pub title: String,
+    pub description: String,
+    pub label: String,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<ActionLinks>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ActionMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionLinks {
+    #[serde(default)]
+    pub actions: Vec<LinkedAction>,
+}
+
+/// One button of a blink. `href` may hold `{name}` placeholders for its
+/// parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedAction {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub href: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<ActionParameter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionParameter {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern_description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<ParameterOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterOption {
+    pub label: String,
+    pub value: String,
+    #[serde(default)]
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionMessage {
+    pub message: String,
+}
+
+/// Response to the POST on a linked action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPostResponse {
+    /// `transaction` (the default), `message`, `post` or `external-link`.
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    /// Base64-encoded transaction for the wallet to sign.
+    #[serde(default)]
+    pub transaction: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub external_link: Option<String>,
+    #[serde(default)]
+    pub links: Option<PostResponseLinks>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostResponseLinks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<NextActionLink>,
+}
+
+/// Action chaining: what follows once the transaction is confirmed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NextActionLink {
+    /// POST `{ account, signature }` to `href`; the answer is the next action.
+    Post { href: String },
+    /// The next action itself.
+    Inline { action: ActionGetResponse },
+}
+
+/// `actions.json` at a website's root, mapping page paths to action APIs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionsJson {
+    pub rules: Vec<ActionRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRule {
+    /// `*` matches one path segment, a trailing `**` any number of them.
+    pub path_pattern: String,
+    /// Relative or absolute; wildcards take the segments `path_pattern` matched.
+    pub api_path: String,
+}
+
+pub struct BlinkClient {
+    http: reqwest::Client,
+}
+
+impl BlinkClient {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+
+    /// The action API URL behind `link`: a `solana-action:` URL, an
+    /// interstitial such as `https://dial.to/?action=solana-action:...`, a
+    /// website page mapped by its `actions.json`, or the API URL itself.
+    ///
+    /// The page's host must pass `guard` before its `actions.json` is
+    /// fetched; the returned URL still has to be checked by the caller.
+    pub async fn resolve(&self, link: &str, guard: &BlinkGuard) -> Result<Url, ActionError> {
+        if let Some(url) = parse_action_scheme(link)? {
+            return Ok(url);
+        }
+        let url = parse_http(link)?;
+        if let Some((_, action)) = url.query_pairs().find(|(key, _)| key == "action") {
+            if let Some(inner) = parse_action_scheme(&action)? {
+                return Ok(inner);
+            }
+            if let Ok(inner) = parse_http(&action) {
+                return Ok(inner);
+            }
+        }
+        guard.check_host(&self.http, &url).await?;
+        if let Some(actions_json) = self.actions_json(&url).await {
+            if let Some(mapped) = map_url(&actions_json.rules, &url) {
+                return Ok(mapped);
+            }
+        }
+        Ok(url)
+    }
+
+    /// `actions.json` at `url`'s origin, if it serves a usable one.
+    pub async fn actions_json(&self, url: &Url) -> Option<ActionsJson> {
+        let actions_json = url.join("/actions.json").ok()?;
+        let response = self.http.get(actions_json).send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json().await.ok()
+    }
+
+    pub async fn metadata(&self, action_url: &Url) -> Result<ActionGetResponse, ActionError> {
+        let response = self
+            .http
+            .get(action_url.clone())
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+        let response = ensure_success(PROVIDER, response).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| ActionError::malformed_response(PROVIDER, e))
+    }
+
+    /// POST `account` (and any parameters not in the URL) to a linked action.
+    pub async fn post(
+        &self,
+        href: &Url,
+        account: &Pubkey,
+        data: Map<String, Value>,
+    ) -> Result<ActionPostResponse, ActionError> {
+        let mut body = json!({ "account": account.to_string() });
+        if !data.is_empty() {
+            body["data"] = Value::Object(data);
+        }
+        let response = self.http.post(href.clone()).json(&body).send().await?;
+        let response = ensure_success(PROVIDER, response).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| ActionError::malformed_response(PROVIDER, e))
+    }
+
+    /// Report a confirmed transaction to a `post` next-action link; the
+    /// answer is the next action in the chain.
+    pub async fn callback(
+        &self,
+        href: &Url,
+        account: &Pubkey,
+        signature: &str,
+    ) -> Result<ActionGetResponse, ActionError> {
+        let body = json!({ "account": account.to_string(), "signature": signature });
+        let response = self.http.post(href.clone()).json(&body).send().await?;
+        let response = ensure_success(PROVIDER, response).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| ActionError::malformed_response(PROVIDER, e))
+    }
+}
+
+/// Fill `href`'s `{name}` placeholders from `params` and resolve it against
+/// `action_url`. Returns the URL and the parameters it did not use.
+pub fn linked_url(
+    action_url: &Url,
+    linked: &LinkedAction,
+    params: &Map<String, Value>,
+) -> Result<(Url, Map<String, Value>), ActionError> {
+    for parameter in &linked.parameters {
+        if parameter.required && !params.contains_key(&parameter.name) {
+            return Err(ActionError::invalid_input(format!(
+                "Missing parameter `{}` for \"{}\"",
+                parameter.name, linked.label
+            )));
+        }
+    }
+
+    let mut unused = params.clone();
+    let mut href = String::new();
+    let mut rest = linked.href.as_str();
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .ok_or_else(|| ActionError::malformed_response(PROVIDER, format!("unclosed placeholder in {}", linked.href)))?;
+        let name = &rest[start + 1..end];
+        let value = unused
+            .remove(name)
+            .ok_or_else(|| ActionError::invalid_input(format!("Missing parameter `{name}` for \"{}\"", linked.label)))?;
+        href.push_str(&rest[..start]);
+        href.push_str(&urlencoding::encode(&param_string(&value)));
+        rest = &rest[end + 1..];
+    }
+    href.push_str(rest);
+
+    let url = action_url
+        .join(&href)
+        .map_err(|e| ActionError::malformed_response(PROVIDER, format!("invalid href {href}: {e}")))?;
+    Ok((url, unused))
+}
+
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_action_scheme(link: &str) -> Result<Option<Url>, ActionError> {
+    let inner = match link.strip_prefix(ACTION_SCHEME) {
+        Some(inner) => inner,
+        None => match link.strip_prefix(SOLANA_PAY_SCHEME) {
+            Some(inner) if inner.starts_with("http") => inner,
+            _ => return Ok(None),
+        },
+    };
+    let decoded = urlencoding::decode(inner).map_err(|e| ActionError::invalid_input(format!("Invalid action URL: {e}")))?;
+    parse_http(&decoded).map(Some)
+}
+
+fn parse_http(link: &str) -> Result<Url, ActionError> {
+    let url = Url::parse(link).map_err(|e| ActionError::invalid_input(format!("Invalid blink URL {link}: {e}")))?;
+    match url.scheme() {
+        "https" | "http" => Ok(url),
+        scheme => Err(ActionError::invalid_input(format!("Unsupported blink URL scheme: {scheme}"))),
+    }
+}
+
+/// Apply the first matching `actions.json` rule to `url`, keeping its query.
+pub fn map_url(rules: &[ActionRule], url: &Url) -> Option<Url> {
+    rules.iter().find_map(|rule| {
+        let captures = match_path(&rule.path_pattern, url.path())?;
+        let mut mapped = url.join(&substitute(&rule.api_path, &captures)).ok()?;
+        if mapped.query().is_none() {
+            mapped.set_query(url.query());
+        }
+        Some(mapped)
+    })
+}
+
+/// The segments matched by each wildcard of `pattern`, in order.
+fn match_path(pattern: &str, path: &str) -> Option<Vec<String>> {
+    if !pattern.starts_with('/') {
+        return None;
+    }
+    let pattern: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    let mut captures = Vec::new();
+    for (i, part) in pattern.iter().enumerate() {
+        match *part {
+            "**" if i == pattern.len() - 1 => {
+                captures.push(segments.get(i..).unwrap_or_default().join("/"));
+                return Some(captures);
+            }
+            "*" => captures.push(segments.get(i)?.to_string()),
+            literal => {
+                if segments.get(i) != Some(&literal) {
+                    return None;
+                }
+            }
+        }
+    }
+    (segments.len() == pattern.len()).then_some(captures)
+}
+
+fn substitute(api_path: &str, captures: &[String]) -> String {
+    let mut captures = captures.iter();
+    let mut out = String::new();
+    let mut rest = api_path;
+    while let Some(i) = rest.find('*') {
+        out.push_str(&rest[..i]);
+        let width = if rest[i..].starts_with("**") { 2 } else { 1 };
+        out.push_str(captures.next().map(String::as_str).unwrap_or_default());
+        rest = &rest[i + width..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path_pattern: &str, api_path: &str) -> ActionRule {
+        ActionRule {
+            path_pattern: path_pattern.to_string(),
+            api_path: api_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn maps_website_urls_through_rules() {
+        let rules = [
+            rule("/buy", "/api/buy"),
+            rule("/trade/*", "/api/trade/*"),
+            rule("/x/**", "https://api.example.com/x/**"),
+        ];
+        let map = |url: &str| map_url(&rules, &Url::parse(url).unwrap()).map(|u| u.to_string());
+
+        assert_eq!(map("https://site.com/buy?ref=1").as_deref(), Some("https://site.com/api/buy?ref=1"));
+        assert_eq!(map("https://site.com/trade/SOL").as_deref(), Some("https://site.com/api/trade/SOL"));
+        assert_eq!(map("https://site.com/x/a/b").as_deref(), Some("https://api.example.com/x/a/b"));
+        assert_eq!(map("https://site.com/trade/SOL/USDC"), None);
+        assert_eq!(map("https://site.com/sell"), None);
+
+        assert_eq!(
+            parse_action_scheme("solana-action:https%3A%2F%2Fsite.com%2Fapi%2Fbuy%3Famount%3D1")
+                .unwrap()
+                .unwrap()
+                .as_str(),
+            "https://site.com/api/buy?amount=1"
+        );
+    }
+}
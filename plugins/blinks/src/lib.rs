@@ -1,7 +1,766 @@
+//! Solana Actions ("blinks") for Solana Agent Kit
+//!
+//! Lets an agent read and execute any blink it comes across: the link is
+//! resolved to its action API, the metadata GET describes the available
+//! buttons, and the POST for the chosen one returns a transaction that the
+//! agent signs and sends like any other.
+//!
+//! Both are screened by a [`BlinkGuard`]: only trusted hosts are executed,
+//! and transactions that could drain the wallet are refused before signing.
+//!
+//! [`BlinkServer`] goes the other way and publishes registry actions as
+//! blinks.
+
+pub mod client;
+pub mod security;
+pub mod server;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use solana_actions_core::actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
+use solana_actions_core::agent::Agent;
+use solana_actions_core::error::ActionError;
+use solana_actions_core::execution::TransactionOutcome;
+use solana_actions_core::inspect::load_addresses;
+use solana_sdk::transaction::VersionedTransaction;
+use url::Url;
+
+use crate::client::{linked_url, ActionGetResponse, BlinkClient, LinkedAction, NextActionLink};
+
+pub use client::{ActionPostResponse, ACTION_SCHEME};
+pub use security::{BlinkGuard, HostStatus, SecurityConfig};
+pub use server::{BlinkAction, BlinkServer};
+
+/// Chains longer than this pause and hand the next action back to the caller.
+const MAX_CHAIN_STEPS: usize = 10;
+
+// =============================================================================
+// GET_BLINK_METADATA Action
+// =============================================================================
+
+#[derive(Debug)]
+pub struct GetBlinkMetadataAction {
+    meta: ActionMetadata,
+    guard: Arc<BlinkGuard>,
+}
+
+impl GetBlinkMetadataAction {
+    pub fn new() -> Self {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "Blink URL: a solana-action: URL, an interstitial link such as https://dial.to/?action=..., or a website page with an actions.json",
+                }
+            },
+            "required": ["url"],
+            "additionalProperties": false,
+        });
+
+        let examples = vec![ActionExample {
+            input: json!({
+                "url": "solana-action:https://example.com/api/donate"
+            }),
+            output: json!({
+                "status": "success",
+                "actionUrl": "https://example.com/api/donate",
+                "hostStatus": "allowed",
+                "metadata": {
+                    "title": "Donate",
+                    "description": "Support the project",
+                    "icon": "https://example.com/icon.png",
+                    "label": "Donate",
+                    "links": {
+                        "actions": [
+                            { "label": "1 SOL", "href": "/api/donate/1" },
+                            {
+                                "label": "Donate",
+                                "href": "/api/donate/{amount}",
+                                "parameters": [{ "name": "amount", "label": "SOL amount", "required": true }]
+                            }
+                        ]
+                    }
+                }
+            }),
+            explanation: "Read a donation blink's buttons and their parameters".to_string(),
+        }];
+
+        let meta = ActionMetadata {
+            name: "GET_BLINK_METADATA".to_string(),
+            similes: vec![
+                "read blink".to_string(),
+                "inspect blink".to_string(),
+                "get action metadata".to_string(),
+                "what does this blink do".to_string(),
+            ],
+            description: "Resolve a Solana Actions (blink) URL and return its title, description, available actions with their parameters, and whether its host is allowed or unknown. Blocked hosts, and unknown ones unless they are allowed by configuration, are refused before anything is fetched from them".to_string(),
+            examples,
+            input_schema,
+        };
+
+        Self {
+            meta,
+            guard: Arc::new(BlinkGuard::default()),
+        }
+    }
+
+    /// Share `guard` (and its registry) with other blink actions.
+    pub fn with_guard(mut self, guard: Arc<BlinkGuard>) -> Self {
+        self.guard = guard;
+        self
+    }
+}
+
+impl Default for GetBlinkMetadataAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for GetBlinkMetadataAction {
+    fn metadata(&self) -> &ActionMetadata {
+        &self.meta
+    }
+
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
+        #[derive(Deserialize)]
+        struct Input {
+            url: String,
+        }
+
+        let parsed: Input = serde_json::from_value(input)?;
+        let client = BlinkClient::new(agent.config.http.clone());
+        let action_url = client.resolve(&parsed.url, &self.guard).await?;
+        let host_status = self.guard.check_host(&agent.config.http, &action_url).await?;
+        let metadata = client.metadata(&action_url).await?;
+
+        Ok(json!({
+            "status": "success",
+            "actionUrl": action_url.to_string(),
+            "hostStatus": host_status,
+            "metadata": metadata,
+        }))
+    }
+}
+
+// =============================================================================
+// EXECUTE_BLINK Action
+// =============================================================================
+
+#[derive(Debug)]
+pub struct ExecuteBlinkAction {
+    meta: ActionMetadata,
+    guard: Arc<BlinkGuard>,
+}
+
+impl ExecuteBlinkAction {
+    pub fn new() -> Self {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "Blink URL: a solana-action: URL, an interstitial link or a website page with an actions.json",
+                },
+                "action": {
+                    "type": "string",
+                    "description": "Label of the linked action to run, as returned by GET_BLINK_METADATA. Optional when the blink has a single action",
+                },
+                "params": {
+                    "type": "object",
+                    "description": "Values for the linked action's parameters, keyed by parameter name",
+                },
+                "steps": {
+                    "type": "array",
+                    "description": "Choices for the following actions of a multi-step blink, in order",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "action": { "type": "string" },
+                            "params": { "type": "object" },
+                        },
+                        "additionalProperties": false,
+                    },
+                },
+                "nextAction": {
+                    "type": "object",
+                    "description": "Resume a paused chain: the nextAction returned by a previous EXECUTE_BLINK, run instead of fetching url",
+                }
+            },
+            "required": ["url"],
+            "additionalProperties": false,
+        });
+
+        let examples = vec![
+            ActionExample {
+                input: json!({
+                    "url": "https://dial.to/?action=solana-action:https://example.com/api/donate",
+                    "action": "Donate",
+                    "params": { "amount": 0.5 }
+                }),
+                output: json!({
+                    "status": "success",
+                    "signature": "5UfgJ5...",
+                    "message": "Thanks for donating!",
+                    "steps": [
+                        { "title": "Donate", "action": "Donate", "signature": "5UfgJ5...", "message": "Thanks for donating!" }
+                    ],
+                    "completed": null,
+                }),
+                explanation: "Donate 0.5 SOL through a donation blink".to_string(),
+            },
+            ActionExample {
+                input: json!({
+                    "url": "https://example.com/api/mint",
+                    "steps": [{ "action": "Reveal" }]
+                }),
+                output: json!({
+                    "status": "success",
+                    "signature": "3Xk9Qa...",
+                    "steps": [
+                        { "title": "Mint", "action": "Mint", "signature": "2hT8Lm..." },
+                        { "title": "Reveal your NFT", "action": "Reveal", "signature": "3Xk9Qa..." }
+                    ],
+                    "completed": { "type": "completed", "title": "Minted!", "description": "Your NFT is revealed", "icon": "https://example.com/nft.png", "label": "Done" },
+                }),
+                explanation: "Mint and then reveal through a two-step blink".to_string(),
+            },
+        ];
+
+        let meta = ActionMetadata {
+            name: "EXECUTE_BLINK".to_string(),
+            similes: vec![
+                "execute blink".to_string(),
+                "run blink".to_string(),
+                "use solana action".to_string(),
+                "click blink".to_string(),
+            ],
+            description: "Execute a Solana Actions (blink) URL: request the transaction for the chosen action with the given parameters, then sign and send it. Untrusted hosts and transactions that hand over authority, approve unknown delegates, close accounts to others or call unexpected programs are refused. Multi-step blinks are followed until they complete; when a step needs a choice that was not given in steps, the chain pauses and returns the nextAction to resume with".to_string(),
+            examples,
+            input_schema,
+        };
+
+        Self {
+            meta,
+            guard: Arc::new(BlinkGuard::default()),
+        }
+    }
+
+    pub fn with_guard(mut self, guard: Arc<BlinkGuard>) -> Self {
+        self.guard = guard;
+        self
+    }
+}
+
+impl Default for ExecuteBlinkAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for ExecuteBlinkAction {
+    fn metadata(&self) -> &ActionMetadata {
+        &self.meta
+    }
+
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
+        #[derive(Deserialize, Default)]
+        struct Step {
+            action: Option<String>,
+            #[serde(default)]
+            params: Map<String, Value>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Input {
+            url: String,
+            action: Option<String>,
+            #[serde(default)]
+            params: Map<String, Value>,
+            #[serde(default)]
+            steps: Vec<Step>,
+            next_action: Option<ActionGetResponse>,
+        }
+
+        let parsed: Input = serde_json::from_value(input)?;
+        let client = BlinkClient::new(agent.config.http.clone());
+        let action_url = client.resolve(&parsed.url, &self.guard).await?;
+        self.guard.check_host(&agent.config.http, &action_url).await?;
+        let mut metadata = match parsed.next_action {
+            Some(metadata) => metadata,
+            None => client.metadata(&action_url).await?,
+        };
+        let account = agent.wallet.pubkey();
+
+        let mut choices = std::iter::once(Step {
+            action: parsed.action,
+            params: parsed.params,
+        })
+        .chain(parsed.steps);
+        let mut transcript: Vec<Value> = Vec::new();
+        let mut signature = None;
+
+        loop {
+            if metadata.kind.as_deref() == Some("completed") {
+                if transcript.is_empty() {
+                    return Err(ActionError::invalid_input(format!("Blink \"{}\" is already completed", metadata.title)));
+                }
+                return Ok(chain_result(signature, transcript, Some(metadata), None));
+            }
+            if metadata.disabled {
+                let reason = metadata.error.as_ref().map_or("no reason given", |e| e.message.as_str());
+                let err = ActionError::invalid_input(format!("Blink \"{}\" is disabled: {reason}", metadata.title));
+                if transcript.is_empty() {
+                    return Err(err);
+                }
+                return Ok(chain_result(signature, transcript, Some(metadata), None));
+            }
+            if transcript.len() >= MAX_CHAIN_STEPS {
+                return Ok(chain_result(signature, transcript, None, Some(metadata)));
+            }
+
+            // Later steps without a usable choice pause the chain instead of failing it.
+            let choice = choices.next().unwrap_or_default();
+            let linked = choose_action(&action_url, &metadata, choice.action.as_deref())
+                .and_then(|linked| linked_url(&action_url, &linked, &choice.params).map(|url| (linked, url)));
+            let (linked, (href, data)) = match linked {
+                Ok(linked) => linked,
+                Err(err) if transcript.is_empty() => return Err(err),
+                Err(_) => return Ok(chain_result(signature, transcript, None, Some(metadata))),
+            };
+            // Once a transaction has been sent, a failure ends the chain with
+            // its transcript rather than an error, so the caller can see what
+            // already landed before trying again.
+            let outcome = async {
+                self.guard.check_host(&agent.config.http, &href).await?;
+                let response = client.post(&href, &account, data).await?;
+
+                let mut step = json!({
+                    "title": metadata.title,
+                    "action": linked.label,
+                    "message": response.message,
+                });
+                if let Some(transaction) = response.transaction.as_deref() {
+                    let transaction = decode_transaction(transaction)?;
+                    let loaded = load_addresses(&agent.client, &transaction).await?.unwrap_or_default();
+                    let warnings = self.guard.check_transaction(&transaction, &account, &loaded)?;
+                    if !warnings.is_empty() {
+                        step["warnings"] = json!(warnings);
+                    }
+                    match agent.execute_transaction(transaction, &[]).await? {
+                        TransactionOutcome::Sent(receipt) => {
+                            step["signature"] = json!(receipt.signature.to_string());
+                            signature = Some(receipt.signature.to_string());
+                        }
+                        // Without a signature there is nothing to chain from.
+                        preview => return Ok(StepOutcome::Preview(preview.to_json())),
+                    }
+                } else if response.kind.as_deref() == Some("message") {
+                    return Err(ActionError::not_implemented("Blinks that ask for a signed message are not supported"));
+                } else if let Some(link) = &response.external_link {
+                    step["externalLink"] = json!(link);
+                }
+                transcript.push(step);
+
+                Ok(match response.links.and_then(|links| links.next) {
+                    None => StepOutcome::Done,
+                    Some(NextActionLink::Inline { action }) => StepOutcome::Next(action),
+                    Some(NextActionLink::Post { href }) => {
+                        let href = action_url.join(&href).map_err(|e| {
+                            ActionError::malformed_response("Blink", format!("invalid next href {href}: {e}"))
+                        })?;
+                        let Some(signature) = &signature else {
+                            return Err(ActionError::malformed_response("Blink", "post next link without a transaction"));
+                        };
+                        self.guard.check_host(&agent.config.http, &href).await?;
+                        StepOutcome::Next(client.callback(&href, &account, signature).await?)
+                    }
+                })
+            }
+            .await;
+
+            metadata = match outcome {
+                Ok(StepOutcome::Next(next)) => next,
+                Ok(StepOutcome::Done) => return Ok(chain_result(signature, transcript, None, None)),
+                Ok(StepOutcome::Preview(preview)) => return Ok(preview),
+                Err(err) if transcript.is_empty() => return Err(err),
+                Err(err) => return Ok(chain_failed(signature, transcript, err)),
+            };
+        }
+    }
+}
+
+/// Where one step of EXECUTE_BLINK leaves the chain.
+enum StepOutcome {
+    Next(ActionGetResponse),
+    Done,
+    /// The step's transaction was simulated or built instead of sent.
+    Preview(Value),
+}
+
+/// Output of EXECUTE_BLINK: every step taken, the terminal `completed`
+/// action if the chain reached one, and the action it paused at otherwise.
+fn chain_result(
+    signature: Option<String>,
+    steps: Vec<Value>,
+    completed: Option<ActionGetResponse>,
+    next_action: Option<ActionGetResponse>,
+) -> Value {
+    let message = steps.last().map(|step| step["message"].clone()).unwrap_or_default();
+    let mut result = json!({
+        "status": if next_action.is_some() { "pending" } else { "success" },
+        "signature": signature,
+        "message": message,
+        "steps": steps,
+        "completed": completed,
+    });
+    if let Some(next_action) = next_action {
+        result["nextAction"] = json!(next_action);
+        result["hint"] = json!("Call EXECUTE_BLINK again with the same url, this nextAction and the action and params to use");
+    }
+    result
+}
+
+/// Output of EXECUTE_BLINK for a chain that failed after sending a
+/// transaction: the steps taken so far and the error that stopped it.
+fn chain_failed(signature: Option<String>, steps: Vec<Value>, err: ActionError) -> Value {
+    let mut result = chain_result(signature, steps, None, None);
+    result["status"] = json!("failed");
+    result["error"] = err.to_json();
+    result
+}
+
+/// The linked action labelled `label`, the only one, or the blink itself
+/// when it has no linked actions.
+fn choose_action(action_url: &Url, metadata: &ActionGetResponse, label: Option<&str>) -> Result<LinkedAction, ActionError> {
+    let actions = metadata.links.as_ref().map(|links| links.actions.as_slice()).unwrap_or_default();
+    if actions.is_empty() {
+        return Ok(LinkedAction {
+            kind: None,
+            href: action_url.to_string(),
+            label: metadata.label.clone(),
+            parameters: Vec::new(),
+        });
+    }
+    let chosen = match label {
+        Some(label) => actions.iter().find(|a| a.label.eq_ignore_ascii_case(label)),
+        None if actions.len() == 1 => actions.first(),
+        None => None,
+    };
+    chosen.cloned().ok_or_else(|| {
+        let labels: Vec<&str> = actions.iter().map(|a| a.label.as_str()).collect();
+        ActionError::invalid_input(format!("Choose one of the blink's actions: {}", labels.join(", ")))
+    })
+}
+
+fn decode_transaction(input: &str) -> Result<VersionedTransaction, ActionError> {
+    let bytes = STANDARD
+        .decode(input)
+        .map_err(|e| ActionError::malformed_response("Blink", format!("invalid base64 transaction: {e}")))?;
+    bincode::deserialize(&bytes)
+        .map_err(|e| ActionError::malformed_response("Blink", format!("invalid transaction: {e}")))
+}
+
+/// Register the blink actions with the default [`SecurityConfig`].
+pub fn register_blink_actions(registry: &mut ActionRegistry) {
+    register_blink_actions_with(registry, SecurityConfig::default());
+}
+
+pub fn register_blink_actions_with(registry: &mut ActionRegistry, security: SecurityConfig) {
+    let guard = Arc::new(BlinkGuard::new(security));
+    registry.register(GetBlinkMetadataAction::new().with_guard(guard.clone()));
+    registry.register(ExecuteBlinkAction::new().with_guard(guard));
+}
+
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{extract::Path, http::StatusCode, routing::get, routing::post, Json, Router};
+    use solana_actions_core::execution::ExecutionMode;
+    use solana_actions_core::wallet::KeypairWallet;
+    use solana_actions_core::{AgentConfig, SenderConfig};
+    use solana_sdk::{
+        hash::Hash,
+        message::{Message, VersionedMessage},
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction,
+    };
+
+    use super::*;
+
+    const RECIPIENT: Pubkey = Pubkey::new_from_array([7; 32]);
+
+    fn transfer_transaction(account: &Pubkey, lamports: u64) -> String {
+        let message = Message::new_with_blockhash(
+            &[system_instruction::transfer(account, &RECIPIENT, lamports)],
+            Some(account),
+            &Hash::new_unique(),
+        );
+        let tx = VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::Legacy(message),
+        };
+        STANDARD.encode(bincode::serialize(&tx).unwrap())
+    }
+
+    fn account(body: &Value) -> Pubkey {
+        body["account"].as_str().unwrap().parse().unwrap()
+    }
+
+    async fn donate(Path(amount): Path<f64>, Json(body): Json<Value>) -> Json<Value> {
+        let lamports = (amount * LAMPORTS_PER_SOL as f64) as u64;
+        Json(json!({
+            "transaction": transfer_transaction(&account(&body), lamports),
+            "message": format!("Thanks for {amount} SOL"),
+        }))
+    }
+
+    /// Just enough JSON-RPC for the sender: accept, then report confirmed.
+    async fn rpc(Json(request): Json<Value>) -> Json<Value> {
+        let result = match request["method"].as_str().unwrap() {
+            "sendTransaction" => {
+                let bytes = STANDARD.decode(request["params"][0].as_str().unwrap()).unwrap();
+                let tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+                json!(tx.signatures[0].to_string())
+            }
+            "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+            "getSignatureStatuses" => json!({
+                "context": { "slot": 1 },
+                "value": [{ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "confirmed" }],
+            }),
+            _ => return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "unsupported" } })),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    /// Blink actions that trust the local test servers.
+    fn registry() -> ActionRegistry {
+        let mut registry = ActionRegistry::new();
+        register_blink_actions_with(
+            &mut registry,
+            SecurityConfig {
+                allowed_hosts: ["127.0.0.1".to_string()].into(),
+                registry_url: None,
+                ..Default::default()
+            },
+        );
+        registry
+    }
+
+    fn action(title: &str, actions: Value) -> Value {
+        json!({
+            "icon": "https://example.com/icon.png",
+            "title": title,
+            "description": title,
+            "label": title,
+            "links": { "actions": actions },
+        })
+    }
+
+    #[tokio::test]
+    async fn resolves_and_executes_blinks() {
+        let app = Router::new()
+            .route(
+                "/actions.json",
+                get(|| async { Json(json!({ "rules": [{ "pathPattern": "/donate", "apiPath": "/api/donate" }] })) }),
+            )
+            .route(
+                "/api/donate",
+                get(|| async {
+                    Json(json!({
+                        "icon": "https://example.com/icon.png",
+                        "title": "Donate",
+                        "description": "Support the project",
+                        "label": "Donate",
+                        "links": { "actions": [
+                            { "label": "1 SOL", "href": "/api/donate/1" },
+                            {
+                                "label": "Donate",
+                                "href": "/api/donate/{amount}",
+                                "parameters": [{ "name": "amount", "required": true }]
+                            }
+                        ] }
+                    }))
+                }),
+            )
+            .route("/api/donate/:amount", post(donate));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let keypair = Keypair::new();
+        let wallet = keypair.pubkey();
+        let agent = Agent::new(Arc::new(KeypairWallet::new(keypair)), "http://localhost:8899")
+            .with_mode(ExecutionMode::BuildOnly);
+        let registry = registry();
+
+        let interstitial = format!(
+            "https://dial.to/?action={}",
+            urlencoding::encode(&format!("{ACTION_SCHEME}{}", urlencoding::encode(&format!("{base}/api/donate"))))
+        );
+        let metadata = registry
+            .execute("GET_BLINK_METADATA", &agent, json!({ "url": interstitial }))
+            .await
+            .unwrap();
+        assert_eq!(metadata["actionUrl"], format!("{base}/api/donate"));
+        assert_eq!(metadata["hostStatus"], "allowed");
+        assert_eq!(metadata["metadata"]["links"]["actions"][1]["parameters"][0]["name"], "amount");
+
+        let err = registry
+            .execute("EXECUTE_BLINK", &agent, json!({ "url": format!("{base}/donate") }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ActionError::InvalidInput { .. }));
+
+        let built = registry
+            .execute(
+                "EXECUTE_BLINK",
+                &agent,
+                json!({ "url": format!("{base}/donate"), "action": "donate", "params": { "amount": 0.5 } }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(built["status"], "built");
+        let tx = decode_transaction(built["transaction"].as_str().unwrap()).unwrap();
+        let keys = tx.message.static_account_keys();
+        assert_eq!(keys[0], wallet);
+        assert!(keys.contains(&RECIPIENT));
+
+        // Hosts nobody vouched for are refused before anything is requested.
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().fallback(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { StatusCode::NOT_FOUND }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let untrusted_url = format!("http://{}/donate", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let mut untrusted = ActionRegistry::new();
+        register_blink_actions_with(
+            &mut untrusted,
+            SecurityConfig {
+                registry_url: None,
+                ..Default::default()
+            },
+        );
+        let err = untrusted
+            .execute("GET_BLINK_METADATA", &agent, json!({ "url": untrusted_url }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ActionError::PolicyViolation { rule, .. } if rule == "unknownHost"));
+        let err = untrusted
+            .execute("EXECUTE_BLINK", &agent, json!({ "url": untrusted_url, "action": "1 SOL" }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ActionError::PolicyViolation { rule, .. } if rule == "unknownHost"));
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn follows_action_chains() {
+        let app = Router::new()
+            .route(
+                "/api/mint",
+                get(|| async { Json(action("Mint", json!([{ "label": "Mint", "href": "/api/mint" }]))) }).post(
+                    |Json(body): Json<Value>| async move {
+                        Json(json!({
+                            "transaction": transfer_transaction(&account(&body), 1),
+                            "links": { "next": { "type": "post", "href": "/api/mint/next" } },
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/api/mint/next",
+                post(|Json(body): Json<Value>| async move {
+                    let mut next = action(
+                        "Reveal your NFT",
+                        json!([{ "label": "Reveal", "href": "/api/reveal" }, { "label": "Skip", "href": "/api/skip" }]),
+                    );
+                    next["description"] = json!(format!("Minted in {}", body["signature"].as_str().unwrap()));
+                    Json(next)
+                }),
+            )
+            .route(
+                "/api/reveal",
+                post(|Json(body): Json<Value>| async move {
+                    Json(json!({
+                        "transaction": transfer_transaction(&account(&body), 2),
+                        "message": "Revealed",
+                        "links": { "next": { "type": "inline", "action": {
+                            "type": "completed",
+                            "icon": "https://example.com/nft.png",
+                            "title": "Minted!",
+                            "description": "All done",
+                            "label": "Done",
+                        } } },
+                    }))
+                }),
+            )
+            .route("/rpc", post(rpc));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config = AgentConfig::builder()
+            .sender(SenderConfig {
+                poll_interval_ms: 10,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let agent = Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), &format!("{base}/rpc")).with_config(config);
+        let registry = registry();
+        let url = format!("{base}/api/mint");
+
+        // Two choices for the second step and none given: the chain pauses.
+        let paused = registry.execute("EXECUTE_BLINK", &agent, json!({ "url": url })).await.unwrap();
+        assert_eq!(paused["status"], "pending");
+        let first = paused["steps"][0]["signature"].as_str().unwrap();
+        assert_eq!(paused["nextAction"]["description"], format!("Minted in {first}"));
+
+        let resumed = registry
+            .execute(
+                "EXECUTE_BLINK",
+                &agent,
+                json!({ "url": url, "nextAction": paused["nextAction"], "action": "Reveal" }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resumed["status"], "success");
+        assert_eq!(resumed["steps"].as_array().unwrap().len(), 1);
+        assert_eq!(resumed["completed"]["title"], "Minted!");
+
+        let full = registry
+            .execute("EXECUTE_BLINK", &agent, json!({ "url": url, "steps": [{ "action": "reveal" }] }))
+            .await
+            .unwrap();
+        let steps = full["steps"].as_array().unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1]["message"], "Revealed");
+        assert_eq!(full["signature"], steps[1]["signature"]);
+        assert_eq!(full["completed"]["type"], "completed");
+
+        // A later step that fails still returns what already landed.
+        let failed = registry
+            .execute("EXECUTE_BLINK", &agent, json!({ "url": url, "steps": [{ "action": "skip" }] }))
+            .await
+            .unwrap();
+        assert_eq!(failed["status"], "failed");
+        assert_eq!(failed["steps"].as_array().unwrap().len(), 1);
+        assert_eq!(failed["signature"], failed["steps"][0]["signature"]);
+        assert_eq!(failed["error"]["code"], "UPSTREAM");
     }
 }
@@ -0,0 +1,486 @@
+//! Screening of external blinks before anything is signed.
+//!
+//! Every action URL is checked against a trusted-host registry: hosts may be
+//! allowed, blocked or unknown, and only allowed hosts are executed unless
+//! [`SecurityConfig::allow_unknown_hosts`] is set. Every transaction a blink
+//! returns is decoded and rejected if it hands over authority, approves an
+//! unknown delegate, closes one of the wallet's accounts to someone else or
+//! calls a program outside the allowlist. Accounts a transaction loads from
+//! address lookup tables are resolved first; a System or Token instruction
+//! whose accounts cannot be resolved is rejected.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_actions_core::error::ActionError;
+use solana_actions_core::inspect::{account_keys, decode_transaction_with, is_token_program, InstructionKind};
+use solana_sdk::{
+    compute_budget, message::v0::LoadedAddresses, pubkey, pubkey::Pubkey, system_instruction::SystemInstruction,
+    system_program, transaction::VersionedTransaction,
+};
+use tokio::sync::OnceCell;
+use url::Url;
+
+/// Dialect's public registry of action hosts.
+pub const DIALECT_REGISTRY_URL: &str = "https://registry.dial.to/v1/list";
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const MEMO_PROGRAM_IDS: [Pubkey; 2] = [
+    pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+    pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo"),
+];
+
+/// Hosts are matched case-insensitively; `*.example.com` also matches every
+/// subdomain of `example.com`. Programs and delegates are base58 addresses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SecurityConfig {
+    pub allowed_hosts: HashSet<String>,
+    /// Checked first: a blocked host stays blocked even if the registry trusts it.
+    pub blocked_hosts: HashSet<String>,
+    /// Whether hosts on neither list, nor in the registry, may be executed.
+    pub allow_unknown_hosts: bool,
+    /// Registry in Dialect's format, fetched on first use and retried until a
+    /// fetch succeeds. `None` keeps
+    /// screening to the lists above.
+    pub registry_url: Option<String>,
+    /// Programs a blink transaction may call besides System, SPL Token,
+    /// Token-2022, Associated Token, Compute Budget and Memo.
+    pub allowed_programs: HashSet<String>,
+    /// Delegates a blink may approve to spend the wallet's tokens.
+    pub trusted_delegates: HashSet<String>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: HashSet::new(),
+            blocked_hosts: HashSet::new(),
+            allow_unknown_hosts: false,
+            registry_url: Some(DIALECT_REGISTRY_URL.to_string()),
+            allowed_programs: HashSet::new(),
+            trusted_delegates: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostStatus {
+    Allowed,
+    Blocked,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reported alongside the result.
+    Warning,
+    /// Stops the blink before the wallet signs.
+    Danger,
+}
+
+/// Something worth knowing about one instruction of a blink's transaction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub instruction: usize,
+    pub message: String,
+}
+
+/// Host verdicts of a registry, keyed by lowercase host.
+#[derive(Debug, Default)]
+struct Registry {
+    trusted: HashSet<String>,
+    malicious: HashSet<String>,
+}
+
+impl Registry {
+    /// Parse `{ "actions": [{ "host": ..., "state": "trusted" | "malicious" | ... }] }`.
+    fn parse(registry: &Value) -> Self {
+        let mut parsed = Self::default();
+        for entry in registry["actions"].as_array().into_iter().flatten() {
+            let Some(host) = entry["host"].as_str() else {
+                continue;
+            };
+            match entry["state"].as_str() {
+                Some("trusted") => parsed.trusted.insert(host.to_lowercase()),
+                Some("malicious") => parsed.malicious.insert(host.to_lowercase()),
+                _ => false,
+            };
+        }
+        parsed
+    }
+}
+
+/// Applies a [`SecurityConfig`]; shared by the blink actions so the
+/// registry is fetched once it can be.
+#[derive(Debug)]
+pub struct BlinkGuard {
+    config: SecurityConfig,
+    allowed_programs: HashSet<Pubkey>,
+    trusted_delegates: HashSet<Pubkey>,
+    registry: OnceCell<Registry>,
+}
+
+impl Default for BlinkGuard {
+    fn default() -> Self {
+        Self::new(SecurityConfig::default())
+    }
+}
+
+impl BlinkGuard {
+    /// Addresses in the config that do not parse are ignored.
+    pub fn new(config: SecurityConfig) -> Self {
+        let parse = |keys: &HashSet<String>| keys.iter().filter_map(|key| key.parse().ok()).collect();
+        Self {
+            allowed_programs: parse(&config.allowed_programs),
+            trusted_delegates: parse(&config.trusted_delegates),
+            config,
+            registry: OnceCell::new(),
+        }
+    }
+
+    pub fn config(&self) -> &SecurityConfig {
+        &self.config
+    }
+
+    pub async fn host_status(&self, http: &reqwest::Client, url: &Url) -> HostStatus {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        if matches_any(&self.config.blocked_hosts, &host) {
+            return HostStatus::Blocked;
+        }
+        if matches_any(&self.config.allowed_hosts, &host) {
+            return HostStatus::Allowed;
+        }
+        let registry = self.registry(http).await;
+        if registry.is_some_and(|registry| registry.malicious.contains(&host)) {
+            HostStatus::Blocked
+        } else if registry.is_some_and(|registry| registry.trusted.contains(&host)) {
+            HostStatus::Allowed
+        } else {
+            HostStatus::Unknown
+        }
+    }
+
+    /// Fails unless `url`'s host may be executed.
+    pub async fn check_host(&self, http: &reqwest::Client, url: &Url) -> Result<HostStatus, ActionError> {
+        let host = url.host_str().unwrap_or_default();
+        match self.host_status(http, url).await {
+            HostStatus::Blocked => Err(violation("blockedHost", format!("{host} is on the blocklist"))),
+            HostStatus::Unknown if !self.config.allow_unknown_hosts => Err(violation(
+                "unknownHost",
+                format!("{host} is not a trusted action host; add it to allowedHosts to use it"),
+            )),
+            status => Ok(status),
+        }
+    }
+
+    /// Findings for every instruction of `tx` that `wallet` should not sign
+    /// blindly, with lookup table accounts resolved from `loaded`.
+    pub fn screen(&self, tx: &VersionedTransaction, wallet: &Pubkey, loaded: &LoadedAddresses) -> Vec<Finding> {
+        let keys = account_keys(tx, loaded);
+        let mut findings = Vec::new();
+        let decoded = decode_transaction_with(tx, loaded);
+        for (index, (decoded, ix)) in decoded.iter().zip(tx.message.instructions()).enumerate() {
+            let mut flag = |severity, message: String| {
+                findings.push(Finding {
+                    severity,
+                    instruction: index,
+                    message,
+                })
+            };
+            if !self.is_allowed_program(&decoded.program_id) {
+                flag(Severity::Danger, format!("Calls unexpected program {}", decoded.program_id));
+                continue;
+            }
+            match &decoded.kind {
+                InstructionKind::TokenSetAuthority { current_authority, .. } if current_authority == wallet => {
+                    flag(Severity::Danger, decoded.to_string());
+                }
+                InstructionKind::TokenApprove { delegate, owner, .. }
+                    if owner == wallet && !self.trusted_delegates.contains(delegate) =>
+                {
+                    flag(Severity::Danger, format!("{decoded} (unknown delegate)"));
+                }
+                InstructionKind::TokenCloseAccount { destination, owner, .. }
+                    if owner == wallet && destination != wallet =>
+                {
+                    flag(Severity::Danger, decoded.to_string());
+                }
+                InstructionKind::Other { .. } if decoded.program_id == system_program::id() => {
+                    // Reassigning the wallet to another program hands over its lamports.
+                    let assigned = match bincode::deserialize(&ix.data) {
+                        Ok(SystemInstruction::Assign { owner }) => Some((0, owner)),
+                        Ok(SystemInstruction::AssignWithSeed { owner, .. }) => Some((1, owner)),
+                        _ => None,
+                    };
+                    if let Some((position, owner)) = assigned {
+                        let account = ix.accounts.get(position).and_then(|i| keys.get(*i as usize));
+                        if account == Some(wallet) {
+                            flag(Severity::Danger, format!("Assign the wallet to program {owner}"));
+                        }
+                    }
+                }
+                // Its accounts could be the wallet's and a drainer's; the
+                // checks above cannot tell, so do not sign it.
+                InstructionKind::Unresolved
+                    if decoded.program_id == system_program::id() || is_token_program(&decoded.program_id) =>
+                {
+                    flag(Severity::Danger, decoded.to_string())
+                }
+                InstructionKind::Unresolved | InstructionKind::TokenUnknown { .. } => {
+                    flag(Severity::Warning, decoded.to_string())
+                }
+                _ => {}
+            }
+        }
+        findings
+    }
+
+    /// Fails on any dangerous finding; returns the warnings otherwise.
+    pub fn check_transaction(
+        &self,
+        tx: &VersionedTransaction,
+        wallet: &Pubkey,
+        loaded: &LoadedAddresses,
+    ) -> Result<Vec<Finding>, ActionError> {
+        let (dangers, warnings): (Vec<Finding>, Vec<Finding>) = self
+            .screen(tx, wallet, loaded)
+            .into_iter()
+            .partition(|finding| finding.severity == Severity::Danger);
+        if dangers.is_empty() {
+            return Ok(warnings);
+        }
+        let reasons: Vec<String> = dangers
+            .iter()
+            .map(|finding| format!("instruction {}: {}", finding.instruction, finding.message))
+            .collect();
+        Err(violation("blinkTransaction", reasons.join("; ")))
+    }
+
+    fn is_allowed_program(&self, program_id: &Pubkey) -> bool {
+        *program_id == system_program::id()
+            || *program_id == compute_budget::id()
+            || *program_id == ASSOCIATED_TOKEN_PROGRAM_ID
+            || is_token_program(program_id)
+            || MEMO_PROGRAM_IDS.contains(program_id)
+            || self.allowed_programs.contains(program_id)
+    }
+
+    /// The configured registry, or `None` if it is unset or cannot be
+    /// fetched (in which case every unlisted host is unknown). A failed
+    /// fetch is not cached, so the next check tries again.
+    async fn registry(&self, http: &reqwest::Client) -> Option<&Registry> {
+        self.registry
+            .get_or_try_init(|| async {
+                let url = self.config.registry_url.as_ref().ok_or(())?;
+                let registry = fetch_json(http, url).await.ok_or(())?;
+                Ok::<_, ()>(Registry::parse(&registry))
+            })
+            .await
+            .ok()
+    }
+}
+
+async fn fetch_json(http: &reqwest::Client, url: &str) -> Option<Value> {
+    let response = http.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+fn matches_any(patterns: &HashSet<String>, host: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.to_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host == domain || host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')),
+            None => host == pattern,
+        }
+    })
+}
+
+fn violation(rule: &str, message: impl Into<String>) -> ActionError {
+    ActionError::PolicyViolation {
+        rule: rule.to_string(),
+        message: message.into(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use axum::{http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+    use serde_json::json;
+    use solana_sdk::{
+        address_lookup_table::AddressLookupTableAccount,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, Message, VersionedMessage},
+        system_instruction,
+    };
+    use spl_token::instruction::{self as token_instruction, AuthorityType};
+
+    use super::*;
+
+    fn transaction(wallet: &Pubkey, instructions: &[Instruction]) -> VersionedTransaction {
+        VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(instructions, Some(wallet), &Hash::new_unique())),
+        }
+    }
+
+    #[tokio::test]
+    async fn screens_hosts_and_transactions() {
+        let guard = BlinkGuard::new(SecurityConfig {
+            allowed_hosts: ["*.example.com".to_string(), "blocked.example.com".to_string()].into(),
+            blocked_hosts: ["blocked.example.com".to_string()].into(),
+            registry_url: None,
+            ..Default::default()
+        });
+        guard.registry.set(Registry::parse(&json!({
+            "actions": [
+                { "host": "dial.to", "state": "trusted" },
+                { "host": "drainer.xyz", "state": "malicious" },
+            ]
+        })))
+        .unwrap();
+        let http = reqwest::Client::new();
+        let status = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            let guard = &guard;
+            let http = &http;
+            async move { guard.host_status(http, &url).await }
+        };
+        assert_eq!(status("https://api.example.com/donate").await, HostStatus::Allowed);
+        assert_eq!(status("https://example.com/donate").await, HostStatus::Allowed);
+        assert_eq!(status("https://blocked.example.com/donate").await, HostStatus::Blocked);
+        assert_eq!(status("https://notexample.com/donate").await, HostStatus::Unknown);
+        assert_eq!(status("https://dial.to/donate").await, HostStatus::Allowed);
+        assert_eq!(status("https://drainer.xyz/claim").await, HostStatus::Blocked);
+        let unknown = Url::parse("https://notexample.com/donate").unwrap();
+        assert!(matches!(
+            guard.check_host(&http, &unknown).await,
+            Err(ActionError::PolicyViolation { rule, .. }) if rule == "unknownHost"
+        ));
+
+        let wallet = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let token = spl_token::id();
+
+        let payment = transaction(&wallet, &[system_instruction::transfer(&wallet, &other, 1)]);
+        assert_eq!(guard.check_transaction(&payment, &wallet, &LoadedAddresses::default()).unwrap(), Vec::new());
+
+        let drains = [
+            token_instruction::set_authority(&token, &token_account, Some(&other), AuthorityType::AccountOwner, &wallet, &[])
+                .unwrap(),
+            token_instruction::approve(&token, &token_account, &other, &wallet, &[], u64::MAX).unwrap(),
+            token_instruction::close_account(&token, &token_account, &other, &wallet, &[]).unwrap(),
+            system_instruction::assign(&wallet, &other),
+            Instruction::new_with_bytes(other, &[1], vec![AccountMeta::new(wallet, true)]),
+        ];
+        for drain in &drains {
+            let tx = transaction(&wallet, &[system_instruction::transfer(&wallet, &other, 1), drain.clone()]);
+            let findings = guard.screen(&tx, &wallet, &LoadedAddresses::default());
+            assert_eq!(findings.len(), 1, "{drain:?}");
+            assert_eq!((findings[0].severity, findings[0].instruction), (Severity::Danger, 1));
+            assert!(guard.check_transaction(&tx, &wallet, &LoadedAddresses::default()).is_err());
+        }
+
+        // Closing to the wallet itself and approving a trusted delegate are fine.
+        let guard = BlinkGuard::new(SecurityConfig {
+            trusted_delegates: [other.to_string()].into(),
+            registry_url: None,
+            ..Default::default()
+        });
+        let tidy = transaction(
+            &wallet,
+            &[
+                token_instruction::close_account(&token, &token_account, &wallet, &wallet, &[]).unwrap(),
+                token_instruction::approve(&token, &token_account, &other, &wallet, &[], 1).unwrap(),
+            ],
+        );
+        assert!(guard.screen(&tidy, &wallet, &LoadedAddresses::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn retries_a_registry_that_failed_to_load() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let app = Router::new().route(
+            "/v1/list",
+            get(move || async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return StatusCode::SERVICE_UNAVAILABLE.into_response();
+                }
+                Json(json!({ "actions": [{ "host": "dial.to", "state": "trusted" }] })).into_response()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let registry_url = format!("http://{}/v1/list", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let guard = BlinkGuard::new(SecurityConfig {
+            registry_url: Some(registry_url),
+            ..Default::default()
+        });
+        let http = reqwest::Client::new();
+        let url = Url::parse("https://dial.to/donate").unwrap();
+        assert_eq!(guard.host_status(&http, &url).await, HostStatus::Unknown);
+        assert_eq!(guard.host_status(&http, &url).await, HostStatus::Allowed);
+        assert_eq!(guard.host_status(&http, &url).await, HostStatus::Allowed);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn screens_accounts_from_lookup_tables() {
+        let wallet = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![delegate],
+        };
+        let approve =
+            token_instruction::approve(&spl_token::id(), &token_account, &delegate, &wallet, &[], u64::MAX).unwrap();
+        let message = v0::Message::try_compile(&wallet, &[approve], &[table], Hash::new_unique()).unwrap();
+        assert!(!message.account_keys.contains(&delegate));
+        let tx = VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::V0(message),
+        };
+        let loaded = LoadedAddresses {
+            writable: Vec::new(),
+            readonly: vec![delegate],
+        };
+
+        // Unresolved, the approval cannot be told apart from a drain.
+        let guard = BlinkGuard::new(SecurityConfig {
+            registry_url: None,
+            ..Default::default()
+        });
+        let findings = guard.screen(&tx, &wallet, &LoadedAddresses::default());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Danger);
+        assert!(guard.check_transaction(&tx, &wallet, &LoadedAddresses::default()).is_err());
+
+        // Resolved, it is an approval to an unknown delegate.
+        let findings = guard.screen(&tx, &wallet, &loaded);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("unknown delegate"), "{}", findings[0].message);
+
+        let guard = BlinkGuard::new(SecurityConfig {
+            trusted_delegates: [delegate.to_string()].into(),
+            registry_url: None,
+            ..Default::default()
+        });
+        assert!(guard.check_transaction(&tx, &wallet, &loaded).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+//! Serves selected registry actions as Solana Action endpoints.
+//!
+//! `GET /api/actions/{NAME}` describes the action, with one parameter per
+//! `input_schema` property; `POST` with `{ "account": ... }` runs it in
+//! [`ExecutionMode::BuildOnly`] on behalf of that account and returns the
+//! unsigned transaction. `/actions.json` maps `/actions/{NAME}` pages to
+//! the API, and every response carries the CORS and version headers blink
+//! clients expect.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderValue, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::{json, Map, Value};
+use solana_actions_core::actions::ActionRegistry;
+use solana_actions_core::agent::Agent;
+use solana_actions_core::error::ActionError;
+use solana_actions_core::execution::ExecutionMode;
+use solana_actions_core::validation::{coerce_str, schema_type};
+use solana_actions_core::wallet::Wallet;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::client::{ActionGetResponse, ActionLinks, ActionParameter, LinkedAction, ParameterOption};
+
+/// Version of the Actions spec these endpoints follow.
+pub const ACTION_VERSION: &str = "2.4";
+/// CAIP-2 id of mainnet-beta, sent in `X-Blockchain-Ids`.
+pub const MAINNET_BLOCKCHAIN_ID: &str = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp";
+/// CAIP-2 id of devnet.
+pub const DEVNET_BLOCKCHAIN_ID: &str = "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1";
+
+/// How one exposed action is presented.
+#[derive(Debug, Clone, Default)]
+pub struct BlinkAction {
+    pub icon: String,
+    /// Defaults to the action name in sentence case.
+    pub title: Option<String>,
+    /// Defaults to the action's description.
+    pub description: Option<String>,
+    /// Button text; defaults to the title.
+    pub label: Option<String>,
+    /// Input fixed by the publisher, e.g. the collection to mint into. These
+    /// are not offered as parameters and cannot be overridden by the caller.
+    pub preset: Map<String, Value>,
+}
+
+pub struct BlinkServer {
+    registry: ActionRegistry,
+    /// RPC client, HTTP config and credentials for per-request agents.
+    agent: Agent,
+    actions: HashMap<String, BlinkAction>,
+    blockchain_id: String,
+}
+
+impl BlinkServer {
+    pub fn new(registry: ActionRegistry, agent: Agent) -> Self {
+        Self {
+            registry,
+            agent,
+            actions: HashMap::new(),
+            blockchain_id: MAINNET_BLOCKCHAIN_ID.to_string(),
+        }
+    }
+
+    /// Publish the registry action `name` at `/api/actions/{name}`.
+    pub fn expose(mut self, name: impl Into<String>, blink: BlinkAction) -> Self {
+        self.actions.insert(name.into(), blink);
+        self
+    }
+
+    /// Network advertised in `X-Blockchain-Ids`, e.g. [`DEVNET_BLOCKCHAIN_ID`].
+    pub fn with_blockchain_id(mut self, id: impl Into<String>) -> Self {
+        self.blockchain_id = id.into();
+        self
+    }
+
+    pub fn router(self) -> Router {
+        let state = Arc::new(self);
+        Router::new()
+            .route("/actions.json", get(actions_json).options(preflight))
+            .route(
+                "/api/actions/:name",
+                get(get_action).post(post_action).options(preflight),
+            )
+            .layer(middleware::map_response_with_state(state.clone(), add_headers))
+            .with_state(state)
+    }
+
+    fn metadata(&self, name: &str) -> Option<ActionGetResponse> {
+        let blink = self.actions.get(name)?;
+        let meta = self.registry.get(name)?.metadata().clone();
+        let title = blink.title.clone().unwrap_or_else(|| sentence_case(&meta.name));
+        let parameters = parameters(&meta.input_schema, &blink.preset);
+        let links = (!parameters.is_empty()).then(|| {
+            let query: Vec<String> = parameters.iter().map(|p| format!("{0}={{{0}}}", p.name)).collect();
+            ActionLinks {
+                actions: vec![LinkedAction {
+                    kind: Some("transaction".to_string()),
+                    href: format!("/api/actions/{name}?{}", query.join("&")),
+                    label: blink.label.clone().unwrap_or_else(|| title.clone()),
+                    parameters,
+                }],
+            }
+        });
+        Some(ActionGetResponse {
+            kind: Some("action".to_string()),
+            icon: blink.icon.clone(),
+            label: blink.label.clone().unwrap_or_else(|| title.clone()),
+            title,
+            description: blink.description.clone().unwrap_or(meta.description),
+            disabled: false,
+            links,
+            error: None,
+        })
+    }
+
+    /// Run `name` for `account`, returning the base64 unsigned transaction.
+    async fn build(&self, name: &str, account: Pubkey, params: HashMap<String, String>) -> Result<String, ActionError> {
+        let blink = self
+            .actions
+            .get(name)
+            .ok_or_else(|| ActionError::UnknownAction { name: name.to_string() })?;
+        let schema = self.registry.get(name).map(|a| a.metadata().input_schema.clone()).unwrap_or_default();
+
+        let mut input = blink.preset.clone();
+        for (key, raw) in params {
+            let property = &schema["properties"][&key];
+            // Unknown and empty parameters are left out; presets win.
+            if property.is_null() || raw.is_empty() || input.contains_key(&key) {
+                continue;
+            }
+            let value = coerce_str(property, &raw).map_err(|e| ActionError::invalid_input(format!("{key}: {e}")))?;
+            input.insert(key, value);
+        }
+
+        let agent = Agent::with_rpc_client(Arc::new(AccountWallet(account)), self.agent.client.clone())
+            .with_config(self.agent.config.clone())
+            .with_credentials(self.agent.credentials.clone())
+            .with_mode(ExecutionMode::BuildOnly);
+        let output = self.registry.execute(name, &agent, Value::Object(input)).await?;
+        match (output["status"].as_str(), output["transaction"].as_str()) {
+            (Some("built"), Some(transaction)) => Ok(transaction.to_string()),
+            _ => Err(ActionError::internal(format!("{name} did not build a transaction"))),
+        }
+    }
+}
+
+/// The account a blink request is for. It never signs: the server only
+/// builds transactions and the caller's own wallet signs them.
+#[derive(Debug)]
+struct AccountWallet(Pubkey);
+
+#[async_trait]
+impl Wallet for AccountWallet {
+    fn pubkey(&self) -> Pubkey {
+        self.0
+    }
+
+    async fn sign_transaction(&self, _tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+        anyhow::bail!("blink requests are built, not signed")
+    }
+
+    async fn sign_all_transactions(&self, _txs: Vec<VersionedTransaction>) -> anyhow::Result<Vec<VersionedTransaction>> {
+        anyhow::bail!("blink requests are built, not signed")
+    }
+}
+
+/// One blink parameter per schema property not fixed by `preset`,
+/// required ones first.
+fn parameters(schema: &Value, preset: &Map<String, Value>) -> Vec<ActionParameter> {
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let mut parameters: Vec<ActionParameter> = schema["properties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| !preset.contains_key(*name))
+        .map(|(name, property)| {
+            let choices: Vec<String> = match (schema_type(property), property["enum"].as_array()) {
+                (_, Some(values)) => values
+                    .iter()
+                    .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                    .collect(),
+                (Some("boolean"), None) => vec!["true".to_string(), "false".to_string()],
+                _ => Vec::new(),
+            };
+            let kind = match schema_type(property) {
+                _ if !choices.is_empty() => "select",
+                Some("number" | "integer") => "number",
+                _ => "text",
+            };
+            ActionParameter {
+                kind: Some(kind.to_string()),
+                name: name.clone(),
+                label: Some(property["description"].as_str().unwrap_or(name).to_string()),
+                required: required.contains(&name.as_str()),
+                pattern: property["pattern"].as_str().map(str::to_string),
+                pattern_description: None,
+                min: property.get("minimum").cloned(),
+                max: property.get("maximum").cloned(),
+                options: choices
+                    .into_iter()
+                    .map(|value| ParameterOption {
+                        label: value.clone(),
+                        value,
+                        selected: false,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    parameters.sort_by_key(|p| !p.required);
+    parameters
+}
+
+fn sentence_case(name: &str) -> String {
+    let lower = name.to_lowercase().replace('_', " ");
+    let mut chars = lower.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "message": message.into() }))).into_response()
+}
+
+async fn add_headers(State(server): State<Arc<BlinkServer>>, mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    for (name, value) in [
+        ("access-control-allow-origin", "*"),
+        ("access-control-allow-methods", "GET,POST,PUT,OPTIONS"),
+        (
+            "access-control-allow-headers",
+            "Content-Type, Authorization, Content-Encoding, Accept-Encoding, X-Action-Version, X-Blockchain-Ids",
+        ),
+        ("access-control-expose-headers", "X-Action-Version, X-Blockchain-Ids"),
+        ("x-action-version", ACTION_VERSION),
+    ] {
+        headers.insert(name, HeaderValue::from_static(value));
+    }
+    if let Ok(id) = HeaderValue::from_str(&server.blockchain_id) {
+        headers.insert("x-blockchain-ids", id);
+    }
+    response
+}
+
+async fn preflight() -> StatusCode {
+    StatusCode::OK
+}
+
+async fn actions_json() -> Json<Value> {
+    Json(json!({
+        "rules": [
+            { "pathPattern": "/actions/*", "apiPath": "/api/actions/*" },
+            { "pathPattern": "/api/actions/**", "apiPath": "/api/actions/**" },
+        ]
+    }))
+}
+
+async fn get_action(State(server): State<Arc<BlinkServer>>, Path(name): Path<String>) -> Response {
+    match server.metadata(&name) {
+        Some(metadata) => Json(metadata).into_response(),
+        None => error_response(StatusCode::NOT_FOUND, format!("Unknown action: {name}")),
+    }
+}
+
+async fn post_action(
+    State(server): State<Arc<BlinkServer>>,
+    Path(name): Path<String>,
+    Query(mut params): Query<HashMap<String, String>>,
+    Json(body): Json<Value>,
+) -> Response {
+    let Some(account) = body["account"].as_str().and_then(|a| a.parse::<Pubkey>().ok()) else {
+        return error_response(StatusCode::BAD_REQUEST, "Body must contain a valid `account`");
+    };
+    // Parameters may also arrive in the body's `data`, as of spec 2.2.
+    if let Some(data) = body["data"].as_object() {
+        for (key, value) in data {
+            let value = value.as_str().map_or_else(|| value.to_string(), str::to_string);
+            params.entry(key.clone()).or_insert(value);
+        }
+    }
+
+    match server.build(&name, account, params).await {
+        Ok(transaction) => Json(json!({ "type": "transaction", "transaction": transaction })).into_response(),
+        Err(err) => {
+            let status = match err {
+                ActionError::UnknownAction { .. } => StatusCode::NOT_FOUND,
+                ActionError::InvalidInput { .. } | ActionError::PolicyViolation { .. } => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            error_response(status, err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_actions_core::actions::{Action, ActionMetadata, ActionResult};
+    use solana_actions_core::wallet::KeypairWallet;
+    use solana_sdk::{
+        hash::Hash,
+        message::{Message, VersionedMessage},
+        signature::Keypair,
+        system_instruction,
+    };
+
+    use super::*;
+
+    /// Pays `amount` lamports to `recipient`; builds without touching RPC.
+    struct Pay(ActionMetadata);
+
+    #[async_trait]
+    impl Action for Pay {
+        fn metadata(&self) -> &ActionMetadata {
+            &self.0
+        }
+
+        async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
+            let recipient: Pubkey = input["recipient"].as_str().unwrap().parse()?;
+            let payer = agent.wallet.pubkey();
+            let ix = system_instruction::transfer(&payer, &recipient, input["amount"].as_u64().unwrap());
+            let message = Message::new_with_blockhash(&[ix], Some(&payer), &Hash::new_unique());
+            let tx = VersionedTransaction {
+                signatures: Vec::new(),
+                message: VersionedMessage::Legacy(message),
+            };
+            Ok(agent.execute_transaction(tx, &[]).await?.to_json())
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_actions_as_blinks() {
+        let recipient = Pubkey::new_unique();
+        let mut registry = ActionRegistry::new();
+        registry.register(Pay(ActionMetadata {
+            name: "PAY".to_string(),
+            similes: Vec::new(),
+            description: "Pay the merchant".to_string(),
+            examples: Vec::new(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "amount": { "type": "integer", "description": "Lamports", "minimum": 1 },
+                    "recipient": { "type": "string" },
+                },
+                "required": ["amount", "recipient"],
+                "additionalProperties": false,
+            }),
+        }));
+        let mut preset = Map::new();
+        preset.insert("recipient".to_string(), json!(recipient.to_string()));
+        let server = BlinkServer::new(registry, Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), "http://localhost:8899"))
+            .expose(
+                "PAY",
+                BlinkAction {
+                    icon: "https://example.com/pay.png".to_string(),
+                    preset,
+                    ..Default::default()
+                },
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, server.router()).await });
+        let http = reqwest::Client::new();
+
+        let response = http.get(format!("{base}/api/actions/PAY")).send().await.unwrap();
+        assert_eq!(response.headers()["access-control-allow-origin"], "*");
+        assert_eq!(response.headers()["x-blockchain-ids"], MAINNET_BLOCKCHAIN_ID);
+        let metadata: ActionGetResponse = response.json().await.unwrap();
+        assert_eq!(metadata.title, "Pay");
+        let linked = &metadata.links.unwrap().actions[0];
+        assert_eq!(linked.href, "/api/actions/PAY?amount={amount}");
+        assert_eq!(linked.parameters.len(), 1);
+        assert_eq!(linked.parameters[0].kind.as_deref(), Some("number"));
+        assert!(linked.parameters[0].required);
+
+        let response = http.get(format!("{base}/api/actions/TRANSFER")).send().await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        // The caller cannot redirect the payment by overriding a preset.
+        let account = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let posted: Value = http
+            .post(format!("{base}/api/actions/PAY?amount=5000&recipient={other}"))
+            .json(&json!({ "account": account.to_string() }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let tx = crate::decode_transaction(posted["transaction"].as_str().unwrap()).unwrap();
+        let keys = tx.message.static_account_keys();
+        assert_eq!(keys[0], account);
+        assert!(keys.contains(&recipient));
+        assert!(!keys.contains(&other));
+
+        let response = http
+            .post(format!("{base}/api/actions/PAY?amount=lots"))
+            .json(&json!({ "account": account.to_string() }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        // The client side resolves the page through actions.json.
+        let client = crate::client::BlinkClient::new(http.clone());
+        let guard = crate::BlinkGuard::new(crate::SecurityConfig {
+            allowed_hosts: ["127.0.0.1".to_string()].into(),
+            registry_url: None,
+            ..Default::default()
+        });
+        let resolved = client.resolve(&format!("{base}/actions/PAY"), &guard).await.unwrap();
+        assert_eq!(resolved.as_str(), format!("{base}/api/actions/PAY"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Turns `run` arguments into an action's JSON input.
+//!
+//! `--json '{...}'` supplies the whole input; each `--key value` then sets
+//! one field, coerced to the type the action's `input_schema` declares for
+//! it. A flag with no value is `true`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{Map, Value};
+use solana_actions_core::validation::coerce_str;
+use solana_actions_core::ExecutionMode;
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    pub input: Value,
+    /// Set by `--dry-run` or `--build-only`.
+    pub mode: Option<ExecutionMode>,
+}
+
+pub fn parse_run_args(schema: &Value, args: &[String]) -> Result<RunArgs> {
+    let mut input = Map::new();
+    let mut mode = None;
+    let mut args = args.iter().peekable();
+
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            bail!("Unexpected argument `{arg}`; use --key value");
+        };
+        let (key, inline) = match flag.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_string())),
+            None => (flag, None),
+        };
+        match key {
+            "dry-run" => mode = Some(ExecutionMode::Simulate),
+            "build-only" => mode = Some(ExecutionMode::BuildOnly),
+            "json" => {
+                let raw = inline
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| anyhow!("--json needs a value"))?;
+                match serde_json::from_str(&raw).context("--json is not valid JSON")? {
+                    Value::Object(fields) => input.extend(fields),
+                    _ => bail!("--json must be an object"),
+                }
+            }
+            _ => {
+                let value = match inline {
+                    Some(value) => Some(value),
+                    None if args.peek().is_some_and(|next| !next.starts_with("--")) => args.next().cloned(),
+                    None => None,
+                };
+                let value = match value {
+                    Some(raw) => coerce_str(&schema["properties"][key], &raw)
+                        .map_err(|e| anyhow!("Invalid value for --{key}: {e}"))?,
+                    None => Value::Bool(true),
+                };
+                input.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    Ok(RunArgs {
+        input: Value::Object(input),
+        mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn coerces_by_schema_type() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "outputMint": { "type": "string" },
+                "inputAmount": { "type": "number" },
+                "slippageBps": { "type": "integer" },
+                "mints": { "type": "array", "items": { "type": "string" } },
+                "verbose": { "type": "boolean" },
+            },
+        });
+
+        let parsed = parse_run_args(
+            &schema,
+            &args(&[
+                "--json",
+                r#"{"outputMint":"old","extra":1}"#,
+                "--outputMint",
+                "123",
+                "--inputAmount",
+                "1.5",
+                "--slippageBps=50",
+                "--mints",
+                "a, b",
+                "--dry-run",
+                "--verbose",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.input,
+            json!({
+                "outputMint": "123",
+                "extra": 1,
+                "inputAmount": 1.5,
+                "slippageBps": 50,
+                "mints": ["a", "b"],
+                "verbose": true,
+            })
+        );
+        assert_eq!(parsed.mode, Some(ExecutionMode::Simulate));
+
+        assert!(parse_run_args(&schema, &args(&["--inputAmount", "lots"])).is_err());
+        assert!(parse_run_args(&schema, &args(&["stray"])).is_err());
+    }
+}
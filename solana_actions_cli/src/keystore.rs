@@ -0,0 +1,118 @@
+//! `solana-actions keystore`: create and manage encrypted keystores.
+//!
+//! Passwords are read from `SOLANA_KEYSTORE_PASSWORD` when it is set and
+//! prompted for otherwise; secrets being imported are always prompted for so
+//! they stay out of shell history.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Args, Subcommand};
+use solana_actions_core::settings::KEYSTORE_PASSWORD_VAR;
+use solana_actions_core::wallet::{KeypairWallet, Wallet};
+use solana_actions_core::{Keystore, KeystoreWallet};
+use zeroize::Zeroizing;
+
+#[derive(Debug, Subcommand)]
+pub enum KeystoreCommand {
+    /// Generate a new keypair into an encrypted keystore.
+    New { path: PathBuf },
+    /// Encrypt an existing key into a new keystore.
+    Import {
+        path: PathBuf,
+        #[command(flatten)]
+        source: ImportSource,
+        /// Account index for --mnemonic, derived at m/44'/501'/<N>'/0'.
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+    },
+    /// Print the keystore's address; no password needed.
+    Address { path: PathBuf },
+    /// Re-encrypt the keystore under a new password.
+    ChangePassword { path: PathBuf },
+    /// Print the secret key in base58, as wallets import it.
+    Export { path: PathBuf },
+}
+
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+pub struct ImportSource {
+    /// Solana CLI keypair file.
+    #[arg(long, value_name = "FILE")]
+    keypair: Option<PathBuf>,
+    /// Prompt for a base58 secret key.
+    #[arg(long)]
+    base58: bool,
+    /// Prompt for a BIP39 mnemonic and its optional passphrase.
+    #[arg(long)]
+    mnemonic: bool,
+}
+
+pub fn run(command: KeystoreCommand) -> Result<()> {
+    match command {
+        KeystoreCommand::New { path } => {
+            let password = new_password()?;
+            let wallet = KeystoreWallet::create(&path, &password)?;
+            println!("{}", wallet.pubkey());
+        }
+        KeystoreCommand::Import { path, source, account } => {
+            let wallet = if let Some(file) = source.keypair {
+                KeypairWallet::from_file(file)?
+            } else if source.base58 {
+                KeypairWallet::from_base58(&prompt("Secret key: ")?)?
+            } else {
+                let phrase = prompt("Mnemonic: ")?;
+                let passphrase = prompt("BIP39 passphrase (empty for none): ")?;
+                KeypairWallet::from_mnemonic(&phrase, &passphrase, account)?
+            };
+            let password = new_password()?;
+            let wallet = KeystoreWallet::import(&path, wallet, &password)?;
+            println!("{}", wallet.pubkey());
+        }
+        KeystoreCommand::Address { path } => {
+            println!("{}", Keystore::read(path)?.pubkey()?);
+        }
+        KeystoreCommand::ChangePassword { path } => {
+            let old = password()?;
+            let wallet = KeystoreWallet::unlock(&path, &old)?;
+            let new = confirmed_password()?;
+            wallet.change_password(&old, &new)?;
+        }
+        KeystoreCommand::Export { path } => {
+            let password = password()?;
+            let wallet = KeystoreWallet::unlock(&path, &password)?;
+            println!("{}", *wallet.export(&password)?);
+        }
+    }
+    Ok(())
+}
+
+fn prompt(label: &str) -> Result<Zeroizing<String>> {
+    Ok(Zeroizing::new(rpassword::prompt_password(label)?))
+}
+
+fn password() -> Result<Zeroizing<String>> {
+    match std::env::var(KEYSTORE_PASSWORD_VAR) {
+        Ok(password) => Ok(Zeroizing::new(password)),
+        Err(_) => prompt("Keystore password: "),
+    }
+}
+
+/// The password for a keystore being written.
+fn new_password() -> Result<Zeroizing<String>> {
+    match std::env::var(KEYSTORE_PASSWORD_VAR) {
+        Ok(password) => Ok(Zeroizing::new(password)),
+        Err(_) => confirmed_password(),
+    }
+}
+
+fn confirmed_password() -> Result<Zeroizing<String>> {
+    let password = prompt("New password: ")?;
+    if password.is_empty() {
+        bail!("Password must not be empty");
+    }
+    if *prompt("Repeat password: ")? != *password {
+        bail!("Passwords do not match");
+    }
+    Ok(password)
+}
@@ -0,0 +1,151 @@
+//! `solana-actions`: list, inspect and run registered actions from a shell.
+//!
+//! ```text
+//! solana-actions list
+//! solana-actions show TRADE
+//! solana-actions run TRADE --outputMint <MINT> --inputAmount 1 --dry-run
+//! solana-actions keystore new agent.keystore
+//! ```
+
+mod args;
+mod keystore;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use solana_actions_core::{register_all_actions, ActionRegistry, Settings};
+
+use crate::args::parse_run_args;
+use crate::keystore::KeystoreCommand;
+
+#[derive(Debug, Parser)]
+#[command(name = "solana-actions", version, about)]
+struct Cli {
+    /// TOML settings file, as read by `solana-actions-mcp`.
+    #[arg(long, global = true, env = "SOLANA_ACTIONS_CONFIG")]
+    config: Option<PathBuf>,
+    /// Overrides `rpc_url` from the settings file.
+    #[arg(long, global = true, env = "SOLANA_RPC_URL")]
+    rpc_url: Option<String>,
+    /// Overrides `keypair_path` from the settings file.
+    #[arg(long, global = true, env = "SOLANA_KEYPAIR_PATH")]
+    keypair: Option<PathBuf>,
+    /// Overrides `keystore_path` from the settings file.
+    #[arg(long, global = true, env = "SOLANA_KEYSTORE_PATH")]
+    keystore: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List registered actions.
+    List {
+        /// Print full metadata as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print an action's description, input schema and examples.
+    Show { action: String },
+    /// Run an action and print its output.
+    ///
+    /// Input comes from `--json '{...}'` and/or `--key value` pairs, which are
+    /// coerced to the schema's types. `--dry-run` simulates instead of
+    /// sending; `--build-only` returns the unsigned transaction.
+    Run {
+        action: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "ARGS")]
+        args: Vec<String>,
+    },
+    /// Create, import, export and re-encrypt keystores.
+    Keystore {
+        #[command(subcommand)]
+        command: KeystoreCommand,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut registry = ActionRegistry::new();
+    register_all_actions(&mut registry);
+    blinks::register_blink_actions(&mut registry);
+
+    match run(cli, &registry).await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli, registry: &ActionRegistry) -> Result<ExitCode> {
+    match cli.command {
+        Command::List { json } => {
+            let mut actions = registry.metadata();
+            actions.sort_by(|a, b| a.name.cmp(&b.name));
+            if json {
+                println!("{}", serde_json::to_string_pretty(&actions)?);
+            } else {
+                let width = actions.iter().map(|a| a.name.len()).max().unwrap_or(0);
+                for meta in &actions {
+                    let summary = meta.description.lines().next().unwrap_or_default();
+                    println!("{:width$}  {summary}", meta.name);
+                }
+            }
+        }
+        Command::Show { action } => {
+            let action = registry.get(&action).ok_or_else(|| anyhow!("Unknown action: {action}"))?;
+            let meta = action.metadata();
+            println!("{}\n\n{}\n", meta.name, meta.description.trim());
+            if !meta.similes.is_empty() {
+                println!("Similes: {}\n", meta.similes.join(", "));
+            }
+            println!("Input schema:\n{}", serde_json::to_string_pretty(&meta.input_schema)?);
+            for (i, example) in meta.examples.iter().enumerate() {
+                println!("\nExample {}: {}", i + 1, example.explanation);
+                println!("input:  {}", example.input);
+                println!("output: {}", example.output);
+            }
+        }
+        Command::Run { action, args } => {
+            let meta = registry
+                .get(&action)
+                .ok_or_else(|| anyhow!("Unknown action: {action}"))?
+                .metadata()
+                .clone();
+            let run_args = parse_run_args(&meta.input_schema, &args)?;
+
+            let mut settings = Settings::load(cli.config.as_deref())?;
+            if let Some(rpc_url) = cli.rpc_url {
+                settings.rpc_url = rpc_url;
+            }
+            if let Some(keypair) = cli.keypair {
+                settings.keypair_path = keypair;
+            }
+            if let Some(keystore) = cli.keystore {
+                settings.keystore_path = Some(keystore);
+            }
+            if let Some(mode) = run_args.mode {
+                settings.mode = mode;
+            }
+            let agent = settings.agent()?;
+
+            return Ok(match registry.execute(&action, &agent, run_args.input).await {
+                Ok(output) => {
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                    ExitCode::SUCCESS
+                }
+                Err(err) => {
+                    eprintln!("{}", serde_json::to_string_pretty(&err.to_json())?);
+                    ExitCode::FAILURE
+                }
+            });
+        }
+        Command::Keystore { command } => keystore::run(command)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
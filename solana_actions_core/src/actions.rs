@@ -1,12 +1,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
-use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::agent::Agent;
+use crate::error::ActionError;
+use crate::middleware::{ActionContext, Middleware, OnError};
+use crate::validation::validate_input;
+
+/// Result type returned by [`Action::call`] and [`ActionRegistry::execute`].
+pub type ActionResult = std::result::Result<Value, ActionError>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionExample {
@@ -28,18 +34,20 @@ pub struct ActionMetadata {
 pub trait Action: Send + Sync {
     fn metadata(&self) -> &ActionMetadata;
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value>;
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult;
 }
 
 #[derive(Default)]
 pub struct ActionRegistry {
     actions: HashMap<String, Arc<dyn Action>>, 
+    middleware: Vec<Arc<dyn Middleware>>,
 }
 
 impl ActionRegistry {
     pub fn new() -> Self {
         Self {
             actions: HashMap::new(),
+            middleware: Vec::new(),
         }
     }
 
@@ -52,25 +60,100 @@ impl ActionRegistry {
         self.actions.insert(name, action);
     }
 
+    /// Add a middleware around every [`execute`](Self::execute) call. The
+    /// first one added is the outermost layer.
+    pub fn add_middleware<M>(&mut self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+    }
+
     pub fn get(&self, name: &str) -> Option<Arc<dyn Action>> {
         self.actions.get(name).cloned()
     }
 
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Action>, ActionError> {
+        self.get(name).ok_or_else(|| ActionError::UnknownAction {
+            name: name.to_string(),
+        })
+    }
+
     pub fn all(&self) -> Vec<Arc<dyn Action>> {
         self.actions.values().cloned().collect()
     }
 
+    /// Check `input` against the named action's `input_schema` without running it.
+    pub fn validate(&self, name: &str, input: &Value) -> Result<(), ActionError> {
+        let action = self.lookup(name)?;
+        let meta = action.metadata();
+        validate_input(&meta.name, &meta.input_schema, input)?;
+        Ok(())
+    }
+
     /// Execute an action by name with the given JSON input.
+    ///
+    /// Registered middleware `before` hooks run first, then the input is
+    /// validated against the action's `input_schema`; a mismatch fails with
+    /// [`ActionError::InvalidInput`] listing every bad field.
     pub async fn execute(
         &self,
         name: &str,
         agent: &Agent,
-        input: Value,
-    ) -> Result<Value> {
-        let action = self
-            .get(name)
-            .ok_or_else(|| anyhow!("Unknown action: {name}"))?;
-        action.call(agent, input).await
+        mut input: Value,
+    ) -> ActionResult {
+        let action = self.lookup(name)?;
+        let meta = action.metadata();
+        let mut ctx = ActionContext {
+            name: &meta.name,
+            metadata: meta,
+            agent,
+            attempt: 1,
+            started: Instant::now(),
+            broadcasts: agent.broadcast_count(),
+        };
+
+        if let Err(err) = self.prepare(&ctx, &mut input).await {
+            return match self.on_error(&ctx, &input, &err).await {
+                OnError::Recover(value) => Ok(value),
+                OnError::Propagate | OnError::Retry => Err(err),
+            };
+        }
+
+        loop {
+            ctx.broadcasts = agent.broadcast_count();
+            match action.call(agent, input.clone()).await {
+                Ok(mut output) => {
+                    for middleware in self.middleware.iter().rev() {
+                        middleware.after(&ctx, &input, &mut output).await?;
+                    }
+                    return Ok(output);
+                }
+                Err(err) => match self.on_error(&ctx, &input, &err).await {
+                    OnError::Propagate => return Err(err),
+                    OnError::Recover(value) => return Ok(value),
+                    OnError::Retry => ctx.attempt += 1,
+                },
+            }
+        }
+    }
+
+    async fn prepare(&self, ctx: &ActionContext<'_>, input: &mut Value) -> Result<(), ActionError> {
+        for middleware in &self.middleware {
+            middleware.before(ctx, input).await?;
+        }
+        validate_input(&ctx.metadata.name, &ctx.metadata.input_schema, input)?;
+        Ok(())
+    }
+
+    async fn on_error(&self, ctx: &ActionContext<'_>, input: &Value, err: &ActionError) -> OnError {
+        for middleware in self.middleware.iter().rev() {
+            match middleware.on_error(ctx, input, err).await {
+                OnError::Propagate => continue,
+                outcome => return outcome,
+            }
+        }
+        OnError::Propagate
     }
 
     /// Return metadata for all registered actions (useful for AI tool schemas).
@@ -1,83 +1,314 @@
+use crate::compute_budget;
+use crate::config::{AgentConfig, Provider};
+use crate::credentials::{CredentialProvider, EnvCredentials};
+use crate::error::ActionError;
+use crate::execution::{self, ExecutionMode, SimulationReport, TransactionOutcome};
+use crate::jito::{self, BundleStatus, JitoClient, JitoConfig, MAX_BUNDLE_TRANSACTIONS};
+use crate::sender::{self, TransactionReceipt};
+use crate::signing;
 use crate::wallet::Wallet;
 use anyhow::{anyhow, Result};
 use solana_actions_token::TokenActions;
-use solana_client::rpc_client::RpcClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction,
     message::{self, VersionedMessage},
     native_token::LAMPORTS_PER_SOL,
     program_pack::Pack,
     pubkey::Pubkey,
+    signer::Signer,
     system_instruction,
     transaction::VersionedTransaction,
 };
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::state::Mint;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// The core struct for interacting with the Solana blockchain.
 pub struct Agent {
     pub client: Arc<RpcClient>,
     pub wallet: Arc<dyn Wallet>,
+    /// Whether actions broadcast, simulate or only build their transactions.
+    pub mode: ExecutionMode,
+    /// HTTP client and provider endpoints used by actions.
+    pub config: AgentConfig,
+    /// Source of provider API keys; defaults to environment variables.
+    pub credentials: Arc<dyn CredentialProvider>,
+    /// Block engine settings; atomic execution is unavailable when `None`.
+    pub jito: Option<JitoConfig>,
+    /// Transactions and bundles handed to the network so far.
+    broadcasts: AtomicU64,
 }
 
 impl Agent {
     pub fn new(wallet: Arc<dyn Wallet>, rpc_url: &str) -> Self {
+        Self::with_rpc_client(wallet, Arc::new(RpcClient::new(rpc_url.to_string())))
+    }
+
+    /// Build an agent around an existing RPC client, so many agent sessions
+    /// can share one connection pool.
+    pub fn with_rpc_client(wallet: Arc<dyn Wallet>, client: Arc<RpcClient>) -> Self {
         Self {
             wallet,
-            client: Arc::new(RpcClient::new(rpc_url.to_string())),
+            client,
+            mode: ExecutionMode::Live,
+            config: AgentConfig::default(),
+            credentials: Arc::new(EnvCredentials::new()),
+            jito: None,
+            broadcasts: AtomicU64::new(0),
         }
     }
-}
 
-/// Implementation of the TokenActions trait for the core Agent.
-#[async_trait::async_trait]
-impl TokenActions for Agent {
-    async fn get_balance(&self, mint: Option<Pubkey>) -> Result<f64> {
-        let owner = self.wallet.pubkey();
-        match mint {
-            None => {
-                // FIX: get_balance is a synchronous (blocking) call.
-                let lamports = self.client.get_balance(&owner)?;
-                Ok(lamports as f64 / LAMPORTS_PER_SOL as f64)
+    pub fn with_config(mut self, config: AgentConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: Arc<dyn CredentialProvider>) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// API key for `provider`, or [`ActionError::Unauthorized`] if none is configured.
+    pub async fn api_key(&self, provider: Provider) -> std::result::Result<String, ActionError> {
+        self.credentials.api_key(provider).await?.ok_or_else(|| {
+            ActionError::unauthorized(
+                Some(&format!("{provider:?}")),
+                format!("No API key configured for {provider:?}"),
+            )
+        })
+    }
+
+    /// Enable atomic execution through the Jito block engine at
+    /// [`Provider::Jito`]'s base URL.
+    pub fn with_jito(mut self, jito: JitoConfig) -> Self {
+        self.jito = Some(jito);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sign and send, simulate, or just encode `tx` depending on [`Agent::mode`].
+    ///
+    /// `extra_signers` are keypairs the action generated itself (e.g. a new
+    /// mint); they sign in every mode so a built transaction only lacks the
+    /// wallet's signature.
+    pub async fn execute_transaction(
+        &self,
+        mut tx: VersionedTransaction,
+        extra_signers: &[&(dyn Signer + Sync)],
+    ) -> std::result::Result<TransactionOutcome, ActionError> {
+        match self.mode {
+            ExecutionMode::Live => {
+                let mut tx = self.wallet.sign_transaction(tx).await?;
+                signing::partial_sign(&mut tx, extra_signers)?;
+                signing::ensure_fully_signed(&tx)?;
+                Ok(TransactionOutcome::Sent(self.send_transaction(&tx).await?))
             }
-            Some(mint_pubkey) => {
-                let ata = get_associated_token_address(&owner, &mint_pubkey);
-                // FIX: get_token_account_balance is also synchronous.
-                let balance = match self.client.get_token_account_balance(&ata) {
-                    Ok(ui_token_amount) => ui_token_amount.ui_amount.unwrap_or(0.0),
-                    Err(_) => 0.0,
-                };
-                Ok(balance)
+            ExecutionMode::Simulate => {
+                signing::pad_signatures(&mut tx);
+                Ok(TransactionOutcome::Simulated(self.simulate_transaction(&tx).await?))
+            }
+            ExecutionMode::BuildOnly => {
+                signing::partial_sign(&mut tx, extra_signers)?;
+                Ok(TransactionOutcome::Built(execution::encode_transaction(&tx)?))
             }
         }
     }
 
-    async fn transfer(&self, to: Pubkey, amount: f64, mint: Option<Pubkey>) -> Result<String> {
+    /// Execute several transactions in order according to [`Agent::mode`].
+    ///
+    /// With `atomic` set, Live mode submits them as one Jito bundle, with a
+    /// tip transaction appended, so either all of them land or none does;
+    /// this needs [`Agent::with_jito`]. Otherwise each is sent after the
+    /// previous one is confirmed, and an error leaves earlier ones landed.
+    /// Each of `extra_signers` signs the transactions that require it.
+    /// Simulations run independently, so later transactions do not see the
+    /// effects of earlier ones.
+    pub async fn execute_transactions(
+        &self,
+        txs: Vec<VersionedTransaction>,
+        extra_signers: &[&(dyn Signer + Sync)],
+        atomic: bool,
+    ) -> std::result::Result<Vec<TransactionOutcome>, ActionError> {
+        if atomic && self.mode == ExecutionMode::Live && !txs.is_empty() {
+            return self.send_bundle(txs, extra_signers).await;
+        }
+        let mut outcomes = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let signers = required_signers(&tx, extra_signers);
+            outcomes.push(self.execute_transaction(tx, &signers).await?);
+        }
+        Ok(outcomes)
+    }
+
+    async fn send_bundle(
+        &self,
+        mut txs: Vec<VersionedTransaction>,
+        extra_signers: &[&(dyn Signer + Sync)],
+    ) -> std::result::Result<Vec<TransactionOutcome>, ActionError> {
+        let config = self.jito.as_ref().ok_or_else(|| {
+            ActionError::invalid_input("Atomic execution needs a Jito block engine; see Agent::with_jito")
+        })?;
+        if txs.len() >= MAX_BUNDLE_TRANSACTIONS {
+            return Err(ActionError::invalid_input(format!(
+                "A bundle holds at most {} transactions besides the tip",
+                MAX_BUNDLE_TRANSACTIONS - 1
+            )));
+        }
+        let client = JitoClient::new(self.config.http.clone(), self.config.base_url(Provider::Jito));
+
+        // The tip shares the last transaction's blockhash, so it expires with the bundle.
+        let payer = self.wallet.pubkey();
+        let tip_account = client.tip_account(config).await?;
+        let blockhash = *txs[txs.len() - 1].message.recent_blockhash();
+        let tip = message::v0::Message::try_compile(
+            &payer,
+            &[system_instruction::transfer(&payer, &tip_account, config.tip_lamports)],
+            &[],
+            blockhash,
+        )?;
+        txs.push(VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(tip),
+        });
+
+        let mut txs = self.wallet.sign_all_transactions(txs).await?;
+        for tx in &mut txs {
+            let signers = required_signers(tx, extra_signers);
+            signing::partial_sign(tx, &signers)?;
+            signing::ensure_fully_signed(tx)?;
+        }
+        self.broadcasts.fetch_add(1, Ordering::SeqCst);
+        let bundle_id = client.send_bundle(&txs).await?;
+
+        let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+        let target = self.config.sender.commitment;
+        // From here on the bundle may land, so errors name its first
+        // transaction for the caller to look up instead of sending again.
+        let signature = Some(txs[0].signatures[0].to_string());
+        let mut status = BundleStatus::Pending;
+        loop {
+            tokio::time::sleep(Duration::from_millis(config.poll_interval_ms)).await;
+            // A failed poll says nothing about the bundle; keep the last status.
+            if let Ok(polled) = client.bundle_status(&bundle_id).await {
+                status = polled;
+            }
+            match status {
+                BundleStatus::Landed {
+                    slot,
+                    commitment: Some(commitment),
+                } if jito::satisfies(commitment, target) => {
+                    txs.pop();
+                    return Ok(txs
+                        .iter()
+                        .map(|tx| {
+                            TransactionOutcome::Sent(TransactionReceipt {
+                                signature: tx.signatures[0],
+                                slot,
+                                commitment,
+                                fee: None,
+                                compute_units_consumed: None,
+                                bundle_id: Some(bundle_id.clone()),
+                            })
+                        })
+                        .collect());
+                }
+                BundleStatus::Failed => {
+                    return Err(ActionError::TransactionFailed {
+                        signature,
+                        message: format!("Jito bundle {bundle_id} failed; none of its transactions landed"),
+                        logs: Vec::new(),
+                    });
+                }
+                _ if Instant::now() >= deadline => {
+                    return Err(ActionError::TransactionFailed {
+                        signature,
+                        message: format!(
+                            "Jito bundle {bundle_id} did not land in time (last status: {status:?}); it may still land"
+                        ),
+                        logs: Vec::new(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Broadcast a signed transaction, rebroadcasting until it reaches the
+    /// commitment in [`SenderConfig`](crate::sender::SenderConfig) or its
+    /// blockhash expires.
+    pub async fn send_transaction(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> std::result::Result<TransactionReceipt, ActionError> {
+        self.send_transaction_via(&self.client, tx).await
+    }
+
+    /// [`Agent::send_transaction`] through another RPC endpoint, e.g. a
+    /// provider's staked connection.
+    pub async fn send_transaction_via(
+        &self,
+        client: &RpcClient,
+        tx: &VersionedTransaction,
+    ) -> std::result::Result<TransactionReceipt, ActionError> {
+        self.broadcasts.fetch_add(1, Ordering::SeqCst);
+        sender::send_transaction(client, &self.config.sender, tx).await
+    }
+
+    /// How many transactions and bundles this agent has tried to broadcast.
+    /// Counted before sending, so a broadcast that failed with a transport
+    /// error, and may still have reached the network, is included.
+    pub fn broadcast_count(&self) -> u64 {
+        self.broadcasts.load(Ordering::SeqCst)
+    }
+
+    /// Run `simulateTransaction` for `tx` (signatures are not verified) and
+    /// report its logs, compute units, fee and balance changes.
+    pub async fn simulate_transaction(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> std::result::Result<SimulationReport, ActionError> {
+        execution::simulate(&self.client, tx).await
+    }
+
+    /// Build an unsigned SOL or SPL token transfer from the agent's wallet,
+    /// creating the recipient's associated token account when needed.
+    pub async fn build_transfer_transaction(
+        &self,
+        to: Pubkey,
+        amount: f64,
+        mint: Option<Pubkey>,
+    ) -> std::result::Result<VersionedTransaction, ActionError> {
         let from_pubkey = self.wallet.pubkey();
         let mut instructions: Vec<Instruction> = Vec::new();
         match mint {
             None => {
                 let lamports = (amount * LAMPORTS_PER_SOL as f64) as u64;
                 if lamports == 0 {
-                    return Err(anyhow!("Transfer amount is too small"));
+                    return Err(ActionError::invalid_input("Transfer amount is too small"));
                 }
                 instructions.push(system_instruction::transfer(&from_pubkey, &to, lamports));
             }
             Some(mint_pubkey) => {
-                let mint_info = self.client.get_account(&mint_pubkey)?;
+                let mint_info = self.client.get_account(&mint_pubkey).await?;
                 let token_program_id = mint_info.owner;
                 let token_mint_account = Mint::unpack(&mint_info.data)?;
                 let decimals = token_mint_account.decimals;
                 let amount_in_base_units = (amount * 10f64.powi(decimals as i32)) as u64;
                 if amount_in_base_units == 0 {
-                    return Err(anyhow!("Transfer amount is too small"));
+                    return Err(ActionError::invalid_input("Transfer amount is too small"));
                 }
 
                 let source_ata = get_associated_token_address(&from_pubkey, &mint_pubkey);
                 let dest_ata = get_associated_token_address(&to, &mint_pubkey);
 
-                if self.client.get_account(&dest_ata).is_err() {
+                if self.client.get_account(&dest_ata).await.is_err() {
                     instructions.push(
                         spl_associated_token_account::instruction::create_associated_token_account(
                             &from_pubkey,
@@ -100,20 +331,88 @@ impl TokenActions for Agent {
             }
         }
 
-        let latest_blockhash = self.client.get_latest_blockhash()?;
+        self.build_transaction(&instructions).await
+    }
+
+    /// Compile `instructions` into an unsigned V0 transaction paid for by the
+    /// wallet, prepending a simulated compute-unit limit and a priority fee
+    /// unless the instructions already set a compute budget or
+    /// [`ComputeBudgetConfig::enabled`](crate::compute_budget::ComputeBudgetConfig::enabled)
+    /// is off.
+    pub async fn build_transaction(
+        &self,
+        instructions: &[Instruction],
+    ) -> std::result::Result<VersionedTransaction, ActionError> {
+        let payer = self.wallet.pubkey();
+        let latest_blockhash = self.client.get_latest_blockhash().await?;
+        let budget = &self.config.compute_budget;
+        let instructions = if budget.enabled && !compute_budget::has_compute_budget(instructions) {
+            compute_budget::with_compute_budget(&self.client, budget, &payer, instructions, latest_blockhash).await?
+        } else {
+            instructions.to_vec()
+        };
         let message = VersionedMessage::V0(message::v0::Message::try_compile(
-            &from_pubkey,
+            &payer,
             &instructions,
             &[],
             latest_blockhash,
         )?);
-        let tx = VersionedTransaction {
+        Ok(VersionedTransaction {
             signatures: vec![],
             message,
-        };
+        })
+    }
+}
+
+/// The subset of `signers` whose signature `tx` requires.
+fn required_signers<'a>(
+    tx: &VersionedTransaction,
+    signers: &[&'a (dyn Signer + Sync)],
+) -> Vec<&'a (dyn Signer + Sync)> {
+    let required = tx.message.header().num_required_signatures as usize;
+    let keys = tx.message.static_account_keys();
+    signers
+        .iter()
+        .copied()
+        .filter(|signer| keys[..required.min(keys.len())].contains(&signer.pubkey()))
+        .collect()
+}
+
+/// Implementation of the TokenActions trait for the core Agent.
+#[async_trait::async_trait]
+impl TokenActions for Agent {
+    async fn get_balance(&self, mint: Option<Pubkey>) -> Result<f64> {
+        let owner = self.wallet.pubkey();
+        match mint {
+            None => {
+                let lamports = self.client.get_balance(&owner).await?;
+                Ok(lamports as f64 / LAMPORTS_PER_SOL as f64)
+            }
+            Some(mint_pubkey) => {
+                let ata = get_associated_token_address(&owner, &mint_pubkey);
+                let balance = match self.client.get_token_account_balance(&ata).await {
+                    Ok(ui_token_amount) => ui_token_amount.ui_amount.unwrap_or(0.0),
+                    Err(_) => 0.0,
+                };
+                Ok(balance)
+            }
+        }
+    }
+
+    /// Always broadcasts; fails unless the agent is in [`ExecutionMode::Live`].
+    /// Use [`Agent::execute_transaction`] to honour the other modes.
+    async fn transfer(&self, to: Pubkey, amount: f64, mint: Option<Pubkey>) -> Result<String> {
+        if self.mode != ExecutionMode::Live {
+            return Err(anyhow!(
+                "transfer() only sends transactions; the agent is in {:?} mode",
+                self.mode
+            ));
+        }
+        let tx = self.build_transfer_transaction(to, amount, mint).await?;
         let signed_tx = self.wallet.sign_transaction(tx).await?;
-        let signature = self.client.send_and_confirm_transaction(&signed_tx)?;
+        signing::ensure_fully_signed(&signed_tx)?;
+        let receipt = self.send_transaction(&signed_tx).await?;
 
-        Ok(signature.to_string())
+        Ok(receipt.signature.to_string())
     }
 }
@@ -0,0 +1,276 @@
+//! Human-in-the-loop signing.
+//!
+//! [`ApprovalWallet`] wraps another [`Wallet`] and holds every signature
+//! request until an [`Approver`] accepts or rejects it. Requests carry a
+//! decoded, human-readable summary of each transaction so the reviewer sees
+//! which programs are called and what moves where.
+
+use std::fmt::Debug;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::ActionError;
+use crate::inspect::{decode_transaction, DecodedInstruction};
+use crate::wallet::Wallet;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub id: u64,
+    pub signer: String,
+    pub transactions: Vec<TransactionSummary>,
+    /// Off-chain messages to sign, as text (lossy for non-UTF-8 bytes).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummary {
+    /// Programs invoked by top-level instructions, in order of first use.
+    pub programs: Vec<String>,
+    /// One readable line per instruction.
+    pub lines: Vec<String>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+impl TransactionSummary {
+    pub fn new(tx: &VersionedTransaction) -> Self {
+        let instructions = decode_transaction(tx);
+        let mut programs: Vec<String> = Vec::new();
+        for ix in &instructions {
+            let program = ix.program_id.to_string();
+            if !programs.contains(&program) {
+                programs.push(program);
+            }
+        }
+        Self {
+            programs,
+            lines: instructions.iter().map(ToString::to_string).collect(),
+            instructions,
+        }
+    }
+}
+
+impl ApprovalRequest {
+    /// Multi-line text for terminals and chat messages.
+    pub fn render(&self) -> String {
+        let mut out = format!("Signature request #{} for {}\n", self.id, self.signer);
+        for (i, tx) in self.transactions.iter().enumerate() {
+            out.push_str(&format!("Transaction {} (programs: {})\n", i + 1, tx.programs.join(", ")));
+            for line in &tx.lines {
+                out.push_str(&format!("  - {line}\n"));
+            }
+        }
+        for message in &self.messages {
+            out.push_str(&format!("Message:\n{message}\n"));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approved,
+    Rejected { reason: Option<String> },
+}
+
+#[async_trait]
+pub trait Approver: Send + Sync + Debug {
+    async fn review(&self, request: &ApprovalRequest) -> ApprovalDecision;
+}
+
+/// Prints each request to stderr and waits for `y` on stdin. Anything else rejects.
+#[derive(Debug, Default)]
+pub struct StdinApprover;
+
+#[async_trait]
+impl Approver for StdinApprover {
+    async fn review(&self, request: &ApprovalRequest) -> ApprovalDecision {
+        let prompt = request.render();
+        let answer = tokio::task::spawn_blocking(move || {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "{prompt}Approve? [y/N] ");
+            let _ = stderr.flush();
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line).map(|_| line)
+        })
+        .await;
+
+        match answer {
+            Ok(Ok(line)) if matches!(line.trim().to_lowercase().as_str(), "y" | "yes") => ApprovalDecision::Approved,
+            Ok(Ok(_)) => ApprovalDecision::Rejected { reason: None },
+            _ => ApprovalDecision::Rejected {
+                reason: Some("could not read from stdin".to_string()),
+            },
+        }
+    }
+}
+
+/// A request waiting for a decision from whoever holds the receiving end of a
+/// [`ChannelApprover`]. Dropping it without answering rejects the request.
+#[derive(Debug)]
+pub struct PendingApproval {
+    pub request: ApprovalRequest,
+    respond: oneshot::Sender<ApprovalDecision>,
+}
+
+impl PendingApproval {
+    pub fn approve(self) {
+        let _ = self.respond.send(ApprovalDecision::Approved);
+    }
+
+    pub fn reject(self, reason: impl Into<String>) {
+        let _ = self.respond.send(ApprovalDecision::Rejected {
+            reason: Some(reason.into()),
+        });
+    }
+}
+
+/// Forwards requests over a channel, for approval from a UI, chat bot or test.
+#[derive(Debug, Clone)]
+pub struct ChannelApprover {
+    sender: mpsc::Sender<PendingApproval>,
+}
+
+impl ChannelApprover {
+    /// Returns the approver and the receiver pending requests arrive on.
+    pub fn new(buffer: usize) -> (Self, mpsc::Receiver<PendingApproval>) {
+        let (sender, receiver) = mpsc::channel(buffer);
+        (Self { sender }, receiver)
+    }
+}
+
+#[async_trait]
+impl Approver for ChannelApprover {
+    async fn review(&self, request: &ApprovalRequest) -> ApprovalDecision {
+        let (respond, decision) = oneshot::channel();
+        let pending = PendingApproval {
+            request: request.clone(),
+            respond,
+        };
+        if self.sender.send(pending).await.is_err() {
+            return ApprovalDecision::Rejected {
+                reason: Some("approval channel closed".to_string()),
+            };
+        }
+        decision.await.unwrap_or(ApprovalDecision::Rejected {
+            reason: Some("request dropped without a decision".to_string()),
+        })
+    }
+}
+
+/// A [`Wallet`] that only signs what its [`Approver`] accepts.
+#[derive(Debug)]
+pub struct ApprovalWallet {
+    inner: Arc<dyn Wallet>,
+    approver: Arc<dyn Approver>,
+    next_id: AtomicU64,
+}
+
+impl ApprovalWallet {
+    pub fn new(inner: Arc<dyn Wallet>, approver: Arc<dyn Approver>) -> Self {
+        Self {
+            inner,
+            approver,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    async fn approve(&self, txs: &[VersionedTransaction], messages: Vec<String>) -> Result<(), ActionError> {
+        let request = ApprovalRequest {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            signer: self.inner.pubkey().to_string(),
+            transactions: txs.iter().map(TransactionSummary::new).collect(),
+            messages,
+        };
+        match self.approver.review(&request).await {
+            ApprovalDecision::Approved => Ok(()),
+            ApprovalDecision::Rejected { reason } => Err(ActionError::unauthorized(
+                None,
+                match reason {
+                    Some(reason) => format!("signature request #{} rejected: {reason}", request.id),
+                    None => format!("signature request #{} rejected", request.id),
+                },
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Wallet for ApprovalWallet {
+    fn pubkey(&self) -> Pubkey {
+        self.inner.pubkey()
+    }
+
+    async fn sign_transaction(&self, tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+        self.approve(std::slice::from_ref(&tx), Vec::new()).await?;
+        self.inner.sign_transaction(tx).await
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        txs: Vec<VersionedTransaction>,
+    ) -> anyhow::Result<Vec<VersionedTransaction>> {
+        self.approve(&txs, Vec::new()).await?;
+        self.inner.sign_all_transactions(txs).await
+    }
+
+    /// Shows the reviewer the message itself rather than its envelope.
+    async fn sign_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        self.approve(&[], vec![String::from_utf8_lossy(message).into_owned()]).await?;
+        self.inner.sign_message(message).await
+    }
+
+    async fn sign_raw_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        self.approve(&[], vec![String::from_utf8_lossy(message).into_owned()]).await?;
+        self.inner.sign_raw_message(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        signature::Keypair,
+        signer::Signer,
+        system_instruction,
+    };
+
+    use super::*;
+    use crate::wallet::KeypairWallet;
+
+    #[tokio::test]
+    async fn signs_only_after_approval() {
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let (approver, mut pending) = ChannelApprover::new(1);
+        let wallet = ApprovalWallet::new(Arc::new(KeypairWallet::new(keypair)), Arc::new(approver));
+
+        let ix = system_instruction::transfer(&payer, &Pubkey::new_unique(), 500_000_000);
+        let message = v0::Message::try_compile(&payer, &[ix], &[], Hash::default()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+
+        let reviewer = tokio::spawn(async move {
+            let first = pending.recv().await.unwrap();
+            assert!(first.request.transactions[0].lines[0].starts_with("Transfer 0.5 SOL"));
+            first.approve();
+            pending.recv().await.unwrap().reject("too much");
+        });
+
+        assert_eq!(wallet.sign_transaction(tx.clone()).await.unwrap().signatures.len(), 1);
+        let err = ActionError::from(wallet.sign_transaction(tx).await.unwrap_err());
+        assert!(matches!(err, ActionError::Unauthorized { .. }));
+        reviewer.await.unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+//! Compute-unit limits and priority fees for transactions built by actions.
+//!
+//! [`Agent::build_transaction`](crate::agent::Agent::build_transaction)
+//! simulates the instructions to size the compute-unit limit, prices the fee
+//! from `getRecentPrioritizationFees` over the writable accounts, and prepends
+//! both `ComputeBudget` instructions.
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+};
+
+use crate::error::ActionError;
+use crate::signing;
+
+/// Most compute units a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ComputeBudgetConfig {
+    /// Set to `false` to compile instructions exactly as the action built them.
+    pub enabled: bool,
+    /// Percentile (0-100) of recent prioritization fees to pay.
+    pub fee_percentile: u8,
+    /// Highest price paid, in micro-lamports per compute unit.
+    pub max_micro_lamports_per_unit: u64,
+    /// Multiplier applied to the simulated compute units.
+    pub unit_limit_margin: f64,
+    /// Limit used when the simulation fails.
+    pub fallback_unit_limit: u32,
+}
+
+impl Default for ComputeBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fee_percentile: 75,
+            max_micro_lamports_per_unit: 100_000,
+            unit_limit_margin: 1.1,
+            fallback_unit_limit: 200_000,
+        }
+    }
+}
+
+impl ComputeBudgetConfig {
+    /// Limit for a transaction that consumed `units` in simulation.
+    pub fn unit_limit(&self, units: Option<u64>) -> u32 {
+        match units {
+            Some(units) if units > 0 => {
+                let limit = (units as f64 * self.unit_limit_margin).ceil() as u64;
+                limit.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+            }
+            _ => self.fallback_unit_limit,
+        }
+    }
+
+    /// Price from the recent fees paid for the accounts, capped.
+    pub fn unit_price(&self, recent_fees: &[u64]) -> u64 {
+        percentile(recent_fees, self.fee_percentile).min(self.max_micro_lamports_per_unit)
+    }
+}
+
+fn percentile(values: &[u64], percentile: u8) -> u64 {
+    if values.is_empty() {
+        return 0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let rank = (percentile.min(100) as usize * (sorted.len() - 1) + 50) / 100;
+    sorted[rank]
+}
+
+/// Whether the caller already set its own compute budget.
+pub(crate) fn has_compute_budget(instructions: &[Instruction]) -> bool {
+    instructions.iter().any(|ix| ix.program_id == compute_budget::id())
+}
+
+/// `instructions` preceded by a sized unit limit and a market unit price.
+pub(crate) async fn with_compute_budget(
+    client: &RpcClient,
+    config: &ComputeBudgetConfig,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    blockhash: Hash,
+) -> Result<Vec<Instruction>, ActionError> {
+    // Simulate with the largest limit so the budget instructions themselves are counted.
+    let mut probe = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+        ComputeBudgetInstruction::set_compute_unit_price(0),
+    ];
+    probe.extend_from_slice(instructions);
+    let mut tx = VersionedTransaction {
+        signatures: vec![],
+        message: VersionedMessage::V0(v0::Message::try_compile(payer, &probe, &[], blockhash)?),
+    };
+    signing::pad_signatures(&mut tx);
+    let sim_config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(client.commitment()),
+        ..Default::default()
+    };
+    let units = match client.simulate_transaction_with_config(&tx, sim_config).await {
+        Ok(response) if response.value.err.is_none() => response.value.units_consumed,
+        _ => None,
+    };
+
+    let mut writable: Vec<Pubkey> = vec![*payer];
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !writable.contains(&meta.pubkey) {
+            writable.push(meta.pubkey);
+        }
+    }
+    // A fee lookup failure should not stop the transaction; pay no priority fee.
+    let recent_fees: Vec<u64> = client
+        .get_recent_prioritization_fees(&writable)
+        .await
+        .map(|fees| fees.into_iter().map(|f| f.prioritization_fee).collect())
+        .unwrap_or_default();
+
+    let mut budgeted = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(config.unit_limit(units)),
+        ComputeBudgetInstruction::set_compute_unit_price(config.unit_price(&recent_fees)),
+    ];
+    budgeted.extend_from_slice(instructions);
+    Ok(budgeted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_limit_and_caps_price() {
+        let config = ComputeBudgetConfig::default();
+        assert_eq!(config.unit_limit(Some(10_000)), 11_000);
+        assert_eq!(config.unit_limit(Some(2_000_000)), MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(config.unit_limit(None), 200_000);
+
+        let fees = [0, 10, 20, 30, 40, 1_000_000];
+        assert_eq!(config.unit_price(&[]), 0);
+        assert_eq!(config.unit_price(&fees[..5]), 30);
+        assert_eq!(
+            ComputeBudgetConfig {
+                fee_percentile: 100,
+                ..config
+            }
+            .unit_price(&fees),
+            100_000
+        );
+    }
+}
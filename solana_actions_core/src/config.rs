@@ -0,0 +1,225 @@
+//! Settings shared by every action an [`Agent`](crate::agent::Agent) runs.
+//!
+//! Actions reach third-party APIs through [`AgentConfig::http`] and build
+//! their URLs from [`AgentConfig::base_url`], so a deployment can point any
+//! provider at a mock server, a proxy or a paid endpoint.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::compute_budget::ComputeBudgetConfig;
+use crate::error::ActionError;
+use crate::sender::SenderConfig;
+
+/// Third-party APIs used by the bundled actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Provider {
+    /// Jupiter quote and swap API.
+    JupiterQuote,
+    /// Jupiter price and limit-order APIs.
+    JupiterApi,
+    /// Jupiter token lists.
+    JupiterTokens,
+    Sanctum,
+    Solayer,
+    /// Helius REST API (transactions, webhooks).
+    Helius,
+    /// Helius RPC endpoint used for priority sends.
+    HeliusRpc,
+    /// Bonfida SNS proxy.
+    Bonfida,
+    DexScreener,
+    Birdeye,
+    CoinGecko,
+    MagicEden,
+    Rugcheck,
+    /// Pyth Hermes price service.
+    Pyth,
+    Orca,
+    Raydium,
+    Meteora,
+    /// Jito block engine (`/api/v1`).
+    Jito,
+}
+
+impl Provider {
+    pub const ALL: [Provider; 18] = [
+        Provider::JupiterQuote,
+        Provider::JupiterApi,
+        Provider::JupiterTokens,
+        Provider::Sanctum,
+        Provider::Solayer,
+        Provider::Helius,
+        Provider::HeliusRpc,
+        Provider::Bonfida,
+        Provider::DexScreener,
+        Provider::Birdeye,
+        Provider::CoinGecko,
+        Provider::MagicEden,
+        Provider::Rugcheck,
+        Provider::Pyth,
+        Provider::Orca,
+        Provider::Raydium,
+        Provider::Meteora,
+        Provider::Jito,
+    ];
+
+    /// Public endpoint used when no override is configured. Never ends in `/`.
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Provider::JupiterQuote => "https://quote-api.jup.ag/v6",
+            Provider::JupiterApi => "https://api.jup.ag",
+            Provider::JupiterTokens => "https://token.jup.ag",
+            Provider::Sanctum => "https://sanctum-extra-api.ngrok.dev/v1",
+            Provider::Solayer => "https://app.solayer.org/api",
+            Provider::Helius => "https://api.helius.xyz/v0",
+            Provider::HeliusRpc => "https://mainnet.helius-rpc.com",
+            Provider::Bonfida => "https://sns-sdk-proxy.bonfida.workers.dev",
+            Provider::DexScreener => "https://api.dexscreener.com",
+            Provider::Birdeye => "https://public-api.birdeye.so",
+            Provider::CoinGecko => "https://api.coingecko.com/api/v3",
+            Provider::MagicEden => "https://api-mainnet.magiceden.dev/v2",
+            Provider::Rugcheck => "https://api.rugcheck.xyz/v1",
+            Provider::Pyth => "https://hermes.pyth.network",
+            Provider::Orca => "https://api.mainnet.orca.so/v1",
+            Provider::Raydium => "https://api-v3.raydium.io",
+            Provider::Meteora => "https://dlmm-api.meteora.ag",
+            Provider::Jito => "https://mainnet.block-engine.jito.wtf/api/v1",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AgentConfig {
+    /// Shared client; cloning it is cheap and reuses its connection pool.
+    pub http: reqwest::Client,
+    /// Compute-unit limit and priority fee added to transactions actions build.
+    pub compute_budget: ComputeBudgetConfig,
+    /// Commitment and rebroadcast settings for sending transactions.
+    pub sender: SenderConfig,
+    base_urls: HashMap<Provider, String>,
+}
+
+impl AgentConfig {
+    pub fn builder() -> AgentConfigBuilder {
+        AgentConfigBuilder::default()
+    }
+
+    /// Base URL for `provider`, without a trailing `/`.
+    pub fn base_url(&self, provider: Provider) -> &str {
+        self.base_urls
+            .get(&provider)
+            .map(String::as_str)
+            .unwrap_or_else(|| provider.default_base_url())
+    }
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        AgentConfigBuilder::default()
+            .build()
+            .expect("default HTTP client configuration is valid")
+    }
+}
+
+#[derive(Debug)]
+pub struct AgentConfigBuilder {
+    timeout: Duration,
+    connect_timeout: Duration,
+    user_agent: String,
+    http: Option<reqwest::Client>,
+    compute_budget: ComputeBudgetConfig,
+    sender: SenderConfig,
+    base_urls: HashMap<Provider, String>,
+}
+
+impl Default for AgentConfigBuilder {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            user_agent: concat!("solana-actions/", env!("CARGO_PKG_VERSION")).to_string(),
+            http: None,
+            compute_budget: ComputeBudgetConfig::default(),
+            sender: SenderConfig::default(),
+            base_urls: HashMap::new(),
+        }
+    }
+}
+
+impl AgentConfigBuilder {
+    /// Total time allowed for one request, including reading the body.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Use a pre-built client instead; timeouts and user agent set on this
+    /// builder are then ignored.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = Some(client);
+        self
+    }
+
+    pub fn base_url(mut self, provider: Provider, url: impl Into<String>) -> Self {
+        let url = url.into();
+        self.base_urls.insert(provider, url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn compute_budget(mut self, compute_budget: ComputeBudgetConfig) -> Self {
+        self.compute_budget = compute_budget;
+        self
+    }
+
+    pub fn sender(mut self, sender: SenderConfig) -> Self {
+        self.sender = sender;
+        self
+    }
+
+    pub fn build(self) -> Result<AgentConfig, ActionError> {
+        let http = match self.http {
+            Some(client) => client,
+            None => reqwest::Client::builder()
+                .timeout(self.timeout)
+                .connect_timeout(self.connect_timeout)
+                .user_agent(self.user_agent)
+                .build()
+                .map_err(|e| ActionError::internal(format!("Failed to build HTTP client: {e}")))?,
+        };
+        Ok(AgentConfig {
+            http,
+            compute_budget: self.compute_budget,
+            sender: self.sender,
+            base_urls: self.base_urls,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_base_url_and_trims_slash() {
+        let config = AgentConfig::builder()
+            .base_url(Provider::MagicEden, "http://127.0.0.1:9000/v2/")
+            .build()
+            .unwrap();
+        assert_eq!(config.base_url(Provider::MagicEden), "http://127.0.0.1:9000/v2");
+        assert_eq!(config.base_url(Provider::Birdeye), "https://public-api.birdeye.so");
+    }
+}
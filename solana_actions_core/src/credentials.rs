@@ -0,0 +1,134 @@
+//! API keys for third-party providers.
+//!
+//! Keys are resolved by the [`Agent`](crate::agent::Agent) through a
+//! [`CredentialProvider`] rather than taken from action input, so they never
+//! appear in prompts, tool schemas or tool-call logs.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::config::Provider;
+use crate::error::ActionError;
+
+#[async_trait]
+pub trait CredentialProvider: Send + Sync + Debug {
+    /// The API key for `provider`, or `None` if none is configured.
+    async fn api_key(&self, provider: Provider) -> Result<Option<String>, ActionError>;
+}
+
+/// Reads `<PREFIX><PROVIDER>_API_KEY` from the environment, e.g.
+/// `HELIUS_API_KEY` or `MAGIC_EDEN_API_KEY` with the default empty prefix.
+#[derive(Debug, Clone, Default)]
+pub struct EnvCredentials {
+    prefix: String,
+}
+
+impl EnvCredentials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self { prefix: prefix.into() }
+    }
+
+    pub fn var_name(&self, provider: Provider) -> String {
+        format!("{}{}_API_KEY", self.prefix, screaming_snake(provider))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for EnvCredentials {
+    async fn api_key(&self, provider: Provider) -> Result<Option<String>, ActionError> {
+        Ok(std::env::var(self.var_name(provider)).ok().filter(|key| !key.is_empty()))
+    }
+}
+
+/// Reads a JSON object mapping provider names to keys, e.g.
+/// `{"helius": "...", "magicEden": "..."}`. The file is read on every lookup,
+/// so rotated keys are picked up without a restart.
+#[derive(Debug, Clone)]
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for FileCredentials {
+    async fn api_key(&self, provider: Provider) -> Result<Option<String>, ActionError> {
+        let contents = tokio::fs::read_to_string(&self.path).await.map_err(|e| {
+            ActionError::internal(format!("Failed to read credentials file {}: {e}", self.path.display()))
+        })?;
+        let mut keys: HashMap<Provider, String> = serde_json::from_str(&contents).map_err(|e| {
+            ActionError::internal(format!("Invalid credentials file {}: {e}", self.path.display()))
+        })?;
+        Ok(keys.remove(&provider))
+    }
+}
+
+/// Keys held in memory, for tests and for embedding applications that load
+/// secrets themselves.
+#[derive(Debug, Clone, Default)]
+pub struct StaticCredentials {
+    keys: HashMap<Provider, String>,
+}
+
+impl StaticCredentials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, provider: Provider, key: impl Into<String>) -> Self {
+        self.keys.insert(provider, key.into());
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticCredentials {
+    async fn api_key(&self, provider: Provider) -> Result<Option<String>, ActionError> {
+        Ok(self.keys.get(&provider).cloned())
+    }
+}
+
+/// `MagicEden` -> `MAGIC_EDEN`.
+fn screaming_snake(provider: Provider) -> String {
+    let name = format!("{provider:?}");
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn resolves_keys_from_env_and_file() {
+        assert_eq!(EnvCredentials::new().var_name(Provider::MagicEden), "MAGIC_EDEN_API_KEY");
+        assert_eq!(
+            EnvCredentials::with_prefix("AGENT_").var_name(Provider::Helius),
+            "AGENT_HELIUS_API_KEY"
+        );
+
+        let path = std::env::temp_dir().join(format!("solana-actions-credentials-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"birdeye": "bird-key"}"#).unwrap();
+        let file = FileCredentials::new(&path);
+        assert_eq!(file.api_key(Provider::Birdeye).await.unwrap().as_deref(), Some("bird-key"));
+        assert_eq!(file.api_key(Provider::Helius).await.unwrap(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -3,17 +3,24 @@
 //! Includes: Sanctum LST, Solayer staking, Lulo lending, and more.
 
 use async_trait::async_trait;
-use anyhow::Result;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use solana_sdk::transaction::VersionedTransaction;
 
-use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry};
+use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
 use crate::agent::Agent;
+use crate::config::Provider;
+use crate::error::{ensure_success, ActionError};
+use crate::execution::TransactionOutcome;
 
-fn base64_decode(input: &str) -> Result<Vec<u8>> {
+/// Decode a base64-encoded transaction returned by `provider`.
+fn decode_transaction(provider: &str, input: &str) -> Result<VersionedTransaction, ActionError> {
     use base64::{Engine as _, engine::general_purpose::STANDARD};
-    Ok(STANDARD.decode(input)?)
+    let bytes = STANDARD
+        .decode(input)
+        .map_err(|e| ActionError::malformed_response(provider, format!("invalid base64 transaction: {e}")))?;
+    bincode::deserialize(&bytes)
+        .map_err(|e| ActionError::malformed_response(provider, format!("invalid transaction: {e}")))
 }
 
 // =============================================================================
@@ -77,7 +84,7 @@ impl Action for GetSanctumPriceAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             mints: Vec<String>,
@@ -94,19 +101,13 @@ impl Action for GetSanctumPriceAction {
             .join("&");
 
         let url = format!(
-            "https://sanctum-extra-api.ngrok.dev/v1/sol-value/current?{}",
-            query
+            "{}/sol-value/current?{}",
+            agent.config.base_url(Provider::Sanctum), query
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch Sanctum prices: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Sanctum", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -175,7 +176,7 @@ impl Action for StakeWithSolayerAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             amount: f64,
@@ -184,11 +185,11 @@ impl Action for StakeWithSolayerAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://app.solayer.org/api/action/restake/ssol?amount={}",
-            parsed.amount
+            "{}/action/restake/ssol?amount={}",
+            agent.config.base_url(Provider::Solayer), parsed.amount
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(&url)
             .header("Content-Type", "application/json")
@@ -197,30 +198,20 @@ impl Action for StakeWithSolayerAction {
             }))
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            let error_data: Value = response.json().await.unwrap_or(json!({}));
-            return Ok(json!({
-                "status": "error",
-                "message": error_data["message"].as_str().unwrap_or("Staking request failed"),
-            }));
-        }
+        let response = ensure_success("Solayer", response).await?;
 
         let data: Value = response.json().await?;
         let tx_b64 = data["transaction"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("No transaction in response"))?;
-
-        let tx_bytes = base64_decode(tx_b64)?;
-        let mut transaction: VersionedTransaction = bincode::deserialize(&tx_bytes)?;
+            .ok_or_else(|| ActionError::malformed_response("Solayer", "missing transaction"))?;
 
-        // Update blockhash
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        transaction.message.set_recent_blockhash(latest_blockhash);
+        let transaction = decode_transaction("Solayer", tx_b64)?;
 
         // Sign and send
-        let signed_tx = agent.wallet.sign_transaction(transaction).await?;
-        let signature = agent.client.send_and_confirm_transaction(&signed_tx)?;
+        let signature = match agent.execute_transaction(transaction, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
 
         Ok(json!({
             "status": "success",
@@ -293,7 +284,7 @@ impl Action for LuloLendAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         #[allow(dead_code)]
         struct Input {
@@ -306,10 +297,9 @@ impl Action for LuloLendAction {
         // TODO: Implement Lulo lending integration
         // Requires Lulo API integration and transaction construction
 
-        Ok(json!({
-            "status": "error",
-            "message": "Lulo lending is not yet fully implemented. Requires Lulo API integration.",
-        }))
+        Err(ActionError::not_implemented(
+            "Lulo lending requires a Lulo API integration that is not available yet",
+        ))
     }
 }
 
@@ -374,7 +364,7 @@ impl Action for GetSanctumLstApyAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             mints: Vec<String>,
@@ -390,19 +380,13 @@ impl Action for GetSanctumLstApyAction {
             .join("&");
 
         let url = format!(
-            "https://sanctum-extra-api.ngrok.dev/v1/apy/latest?{}",
-            query
+            "{}/apy/latest?{}",
+            agent.config.base_url(Provider::Sanctum), query
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch Sanctum APY: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Sanctum", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -469,7 +453,7 @@ impl Action for GetDriftMarketsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             marketType: Option<String>,
@@ -570,7 +554,7 @@ impl Action for GetDefiRatesAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             token: String,
@@ -664,7 +648,7 @@ impl Action for SwapOnRaydiumAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         #[allow(dead_code)]
         struct Input {
@@ -679,11 +663,9 @@ impl Action for SwapOnRaydiumAction {
         // Note: Direct Raydium swaps require complex SDK integration
         // For most use cases, Jupiter (TRADE action) routes through Raydium automatically
 
-        Ok(json!({
-            "status": "info",
-            "message": "For swaps, use the TRADE action which routes through Jupiter and includes Raydium pools automatically for best pricing.",
-            "recommendation": "Use TRADE action instead",
-        }))
+        Err(ActionError::not_implemented(
+            "Direct Raydium swaps are not supported; use the TRADE action, which routes through Jupiter and includes Raydium pools",
+        ))
     }
 }
 
@@ -748,7 +730,7 @@ impl Action for GetOrcaWhirlpoolsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenMint: Option<String>,
@@ -756,19 +738,13 @@ impl Action for GetOrcaWhirlpoolsAction {
 
         let parsed: Input = serde_json::from_value(input)?;
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
-            .get("https://api.mainnet.orca.so/v1/whirlpool/list")
+            .get(format!("{}/whirlpool/list", agent.config.base_url(Provider::Orca)))
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Orca API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Orca", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -860,7 +836,7 @@ impl Action for GetRaydiumPoolsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             #[serde(rename = "type")]
@@ -871,28 +847,22 @@ impl Action for GetRaydiumPoolsAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = if let Some(ref ids) = parsed.poolIds {
-            format!("https://api-v3.raydium.io/pools/info/ids?ids={}", ids)
+            format!("{}/pools/info/ids?ids={}", agent.config.base_url(Provider::Raydium), ids)
         } else {
             match parsed.pool_type.as_deref() {
-                Some("concentrated") => "https://api-v3.raydium.io/pools/info/list?poolType=concentrated&poolSortField=default&sortType=desc&pageSize=100&page=1".to_string(),
-                Some("standard") => "https://api-v3.raydium.io/pools/info/list?poolType=standard&poolSortField=default&sortType=desc&pageSize=100&page=1".to_string(),
-                _ => "https://api-v3.raydium.io/pools/info/list?poolType=all&poolSortField=default&sortType=desc&pageSize=100&page=1".to_string(),
+                Some("concentrated") => format!("{}/pools/info/list?poolType=concentrated&poolSortField=default&sortType=desc&pageSize=100&page=1", agent.config.base_url(Provider::Raydium)),
+                Some("standard") => format!("{}/pools/info/list?poolType=standard&poolSortField=default&sortType=desc&pageSize=100&page=1", agent.config.base_url(Provider::Raydium)),
+                _ => format!("{}/pools/info/list?poolType=all&poolSortField=default&sortType=desc&pageSize=100&page=1", agent.config.base_url(Provider::Raydium)),
             }
         };
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Raydium API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Raydium", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -964,7 +934,7 @@ impl Action for GetMeteoraPoolsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenMint: Option<String>,
@@ -972,19 +942,13 @@ impl Action for GetMeteoraPoolsAction {
 
         let parsed: Input = serde_json::from_value(input)?;
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
-            .get("https://dlmm-api.meteora.ag/pair/all")
+            .get(format!("{}/pair/all", agent.config.base_url(Provider::Meteora)))
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Meteora API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Meteora", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1065,20 +1029,14 @@ impl Action for GetJupiterRouteMapAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, _input: Value) -> Result<Value> {
-        let client = reqwest::Client::new();
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
+        let client = &agent.config.http;
         let response = client
-            .get("https://quote-api.jup.ag/v6/indexed-route-map")
+            .get(format!("{}/indexed-route-map", agent.config.base_url(Provider::JupiterQuote)))
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Jupiter API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Jupiter", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -0,0 +1,394 @@
+//! Typed errors returned by actions.
+//!
+//! Every action reports failure through [`ActionError`] so orchestration code
+//! can tell a retryable rate limit from a bad argument or a failed transaction.
+//! [`ActionError::to_json`] gives the stable shape handed back to LLMs.
+
+use serde::Serialize;
+use serde_json::Value;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use thiserror::Error;
+
+use crate::validation::{FieldError, ValidationError};
+
+/// Upstream error bodies are cut to this many characters before being surfaced.
+const MAX_UPSTREAM_BODY: usize = 500;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE", rename_all_fields = "camelCase")]
+pub enum ActionError {
+    /// The caller supplied bad arguments; fix the call and retry.
+    #[error("{message}")]
+    InvalidInput {
+        message: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<FieldError>,
+    },
+
+    /// No action is registered under the requested name.
+    #[error("Unknown action: {name}")]
+    UnknownAction { name: String },
+
+    /// A third-party HTTP API failed or returned something unusable.
+    #[error("{provider} API error{}: {message}", status.map(|s| format!(" ({s})")).unwrap_or_default())]
+    Upstream {
+        provider: String,
+        status: Option<u16>,
+        message: String,
+    },
+
+    /// A provider (or the RPC node) throttled the request.
+    #[error("{provider} rate limit exceeded{}", retry_after_secs.map(|s| format!("; retry after {s}s")).unwrap_or_default())]
+    RateLimited {
+        provider: String,
+        retry_after_secs: Option<u64>,
+    },
+
+    /// The Solana RPC node returned an error unrelated to transaction execution.
+    #[error("RPC error: {message}")]
+    Rpc { message: String },
+
+    /// A transaction was rejected in preflight or failed on-chain.
+    #[error("Transaction failed: {message}")]
+    TransactionFailed {
+        signature: Option<String>,
+        message: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        logs: Vec<String>,
+    },
+
+    /// The action exists but its backing integration is not available yet.
+    #[error("Not implemented: {message}")]
+    NotImplemented { message: String },
+
+    /// Missing or rejected credentials, or a signer refused the request.
+    #[error("Unauthorized: {message}")]
+    Unauthorized {
+        provider: Option<String>,
+        message: String,
+    },
+
+    /// The configured policy forbids this action or transaction.
+    #[error("Policy violation ({rule}): {message}")]
+    PolicyViolation { rule: String, message: String },
+
+    /// The call did not finish within its deadline. It may still have had
+    /// effects, e.g. a transaction that lands later.
+    #[error("Timed out after {timeout_ms} ms")]
+    Timeout { timeout_ms: u64 },
+
+    /// Anything that does not fit the categories above.
+    #[error("{message}")]
+    Internal { message: String },
+}
+
+impl ActionError {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput {
+            message: message.into(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn upstream(provider: impl Into<String>, status: Option<u16>, message: impl Into<String>) -> Self {
+        Self::Upstream {
+            provider: provider.into(),
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// A provider answered successfully but the payload was not what we expected.
+    pub fn malformed_response(provider: impl Into<String>, detail: impl std::fmt::Display) -> Self {
+        Self::upstream(provider, None, format!("unexpected response: {detail}"))
+    }
+
+    pub fn rpc(message: impl Into<String>) -> Self {
+        Self::Rpc {
+            message: message.into(),
+        }
+    }
+
+    pub fn not_implemented(message: impl Into<String>) -> Self {
+        Self::NotImplemented {
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized(provider: Option<&str>, message: impl Into<String>) -> Self {
+        Self::Unauthorized {
+            provider: provider.map(str::to_string),
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+        }
+    }
+
+    /// Stable machine-readable code, e.g. `RATE_LIMITED`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidInput { .. } => "INVALID_INPUT",
+            Self::UnknownAction { .. } => "UNKNOWN_ACTION",
+            Self::Upstream { .. } => "UPSTREAM",
+            Self::RateLimited { .. } => "RATE_LIMITED",
+            Self::Rpc { .. } => "RPC",
+            Self::TransactionFailed { .. } => "TRANSACTION_FAILED",
+            Self::NotImplemented { .. } => "NOT_IMPLEMENTED",
+            Self::Unauthorized { .. } => "UNAUTHORIZED",
+            Self::PolicyViolation { .. } => "POLICY_VIOLATION",
+            Self::Timeout { .. } => "TIMEOUT",
+            Self::Internal { .. } => "INTERNAL",
+        }
+    }
+
+    /// Whether repeating the same call later has a reasonable chance of succeeding.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Rpc { .. } => true,
+            Self::Upstream { status, .. } => status.is_none_or(|s| s >= 500),
+            _ => false,
+        }
+    }
+
+    /// JSON form handed back to LLMs and remote callers.
+    ///
+    /// Always contains `status: "error"`, `code`, `message` and `retryable`,
+    /// plus the variant's own fields in camelCase.
+    pub fn to_json(&self) -> Value {
+        let mut value = serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Default::default()));
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("status".to_string(), Value::from("error"));
+            obj.insert("code".to_string(), Value::from(self.code()));
+            obj.insert("message".to_string(), Value::from(self.to_string()));
+            obj.insert("retryable".to_string(), Value::from(self.is_retryable()));
+        }
+        value
+    }
+}
+
+/// Map a non-2xx provider response to the matching [`ActionError`], passing
+/// successful responses through untouched.
+pub async fn ensure_success(
+    provider: &str,
+    response: reqwest::Response,
+) -> Result<reqwest::Response, ActionError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    if status.as_u16() == 429 {
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+        return Err(ActionError::RateLimited {
+            provider: provider.to_string(),
+            retry_after_secs,
+        });
+    }
+
+    let mut body = response.text().await.unwrap_or_default();
+    if body.len() > MAX_UPSTREAM_BODY {
+        let cut = (0..=MAX_UPSTREAM_BODY).rev().find(|i| body.is_char_boundary(*i)).unwrap_or(0);
+        body.truncate(cut);
+        body.push('…');
+    }
+    let message = if body.is_empty() {
+        status.canonical_reason().unwrap_or("request failed").to_string()
+    } else {
+        body
+    };
+
+    if status.as_u16() == 401 || status.as_u16() == 403 {
+        return Err(ActionError::unauthorized(Some(provider), message));
+    }
+
+    Err(ActionError::upstream(provider, Some(status.as_u16()), message))
+}
+
+impl From<ValidationError> for ActionError {
+    fn from(err: ValidationError) -> Self {
+        Self::InvalidInput {
+            message: err.to_string(),
+            errors: err.errors,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ActionError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::invalid_input(format!("Invalid input: {err}"))
+    }
+}
+
+impl From<solana_sdk::pubkey::ParsePubkeyError> for ActionError {
+    fn from(err: solana_sdk::pubkey::ParsePubkeyError) -> Self {
+        Self::invalid_input(format!("Invalid public key: {err}"))
+    }
+}
+
+impl From<solana_sdk::program_error::ProgramError> for ActionError {
+    fn from(err: solana_sdk::program_error::ProgramError) -> Self {
+        Self::internal(format!("Failed to build instruction: {err}"))
+    }
+}
+
+impl From<solana_sdk::message::CompileError> for ActionError {
+    fn from(err: solana_sdk::message::CompileError) -> Self {
+        Self::internal(format!("Failed to compile transaction message: {err}"))
+    }
+}
+
+impl From<solana_sdk::signer::SignerError> for ActionError {
+    fn from(err: solana_sdk::signer::SignerError) -> Self {
+        Self::internal(format!("Failed to sign transaction: {err}"))
+    }
+}
+
+impl From<reqwest::Error> for ActionError {
+    fn from(err: reqwest::Error) -> Self {
+        let provider = err
+            .url()
+            .and_then(|u| u.host_str())
+            .unwrap_or("HTTP")
+            .to_string();
+        // The URL may carry an API key in its query string.
+        let status = err.status();
+        let err = err.without_url();
+
+        match status {
+            Some(status) if status.as_u16() == 429 => Self::RateLimited {
+                provider,
+                retry_after_secs: None,
+            },
+            Some(status) => Self::upstream(provider, Some(status.as_u16()), redact_api_keys(&err.to_string())),
+            None if err.is_decode() => Self::malformed_response(provider, redact_api_keys(&err.to_string())),
+            None => Self::upstream(provider, None, redact_api_keys(&err.to_string())),
+        }
+    }
+}
+
+impl From<ClientError> for ActionError {
+    fn from(err: ClientError) -> Self {
+        match err.kind {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                message,
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+                ..
+            }) => Self::TransactionFailed {
+                signature: None,
+                message,
+                logs: result.logs.unwrap_or_default(),
+            },
+            ClientErrorKind::TransactionError(tx_err) => Self::TransactionFailed {
+                signature: None,
+                message: tx_err.to_string(),
+                logs: Vec::new(),
+            },
+            ClientErrorKind::Reqwest(e) if e.status().map(|s| s.as_u16()) == Some(429) => {
+                Self::RateLimited {
+                    provider: "RPC".to_string(),
+                    retry_after_secs: None,
+                }
+            }
+            // Provider RPC URLs carry their API key in the query string.
+            ClientErrorKind::Reqwest(e) => Self::rpc(redact_api_keys(&e.without_url().to_string())),
+            kind => Self::rpc(redact_api_keys(&kind.to_string())),
+        }
+    }
+}
+
+/// Query parameters that hold provider API keys in request URLs.
+const KEY_PARAMS: [&str; 3] = ["api-key=", "api_key=", "apiKey="];
+
+/// Blank out API keys passed as query parameters in `message`, in case a
+/// request URL made it into an error message some other way.
+fn redact_api_keys(message: &str) -> String {
+    let mut out = message.to_string();
+    for param in KEY_PARAMS {
+        let mut from = 0;
+        while let Some(found) = out[from..].find(param) {
+            let start = from + found + param.len();
+            let end = out[start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '%'))
+                .map_or(out.len(), |i| start + i);
+            out.replace_range(start..end, "REDACTED");
+            from = start + "REDACTED".len();
+        }
+    }
+    out
+}
+
+impl From<anyhow::Error> for ActionError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ActionError>() {
+            Ok(e) => return e,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<ValidationError>() {
+            Ok(e) => return e.into(),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<ClientError>() {
+            Ok(e) => return e.into(),
+            Err(err) => err,
+        };
+        let err = match err.downcast::<reqwest::Error>() {
+            Ok(e) => return e.into(),
+            Err(err) => err,
+        };
+        Self::internal(redact_api_keys(&format!("{err:#}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_shape_is_stable() {
+        let err = ActionError::RateLimited {
+            provider: "Birdeye".to_string(),
+            retry_after_secs: Some(3),
+        };
+        let json = err.to_json();
+
+        assert_eq!(json["status"], "error");
+        assert_eq!(json["code"], "RATE_LIMITED");
+        assert_eq!(json["provider"], "Birdeye");
+        assert_eq!(json["retryAfterSecs"], 3);
+        assert_eq!(json["retryable"], true);
+        assert_eq!(json["message"], "Birdeye rate limit exceeded; retry after 3s");
+    }
+
+    #[tokio::test]
+    async fn errors_do_not_leak_api_keys() {
+        let url = "http://127.0.0.1:1/v0/transactions?api-key=secret-helius-key";
+        let err: ActionError = reqwest::get(url).await.unwrap_err().into();
+        assert!(!err.to_json().to_string().contains("secret-helius-key"), "{err}");
+
+        let rpc = solana_client::nonblocking::rpc_client::RpcClient::new(url.to_string());
+        let err: ActionError = rpc.get_slot().await.unwrap_err().into();
+        assert!(matches!(err, ActionError::Rpc { .. }));
+        assert!(!err.to_json().to_string().contains("secret-helius-key"), "{err}");
+
+        assert_eq!(
+            redact_api_keys("error for url (https://x.io/?api-key=abc-123&cluster=devnet)"),
+            "error for url (https://x.io/?api-key=REDACTED&cluster=devnet)"
+        );
+    }
+
+    #[test]
+    fn anyhow_round_trip_keeps_variant() {
+        let original = ActionError::not_implemented("Lulo lending");
+        let wrapped: anyhow::Error = original.into();
+        assert!(matches!(ActionError::from(wrapped), ActionError::NotImplemented { .. }));
+    }
+}
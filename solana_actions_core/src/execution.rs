@@ -0,0 +1,256 @@
+//! How an [`Agent`](crate::agent::Agent) handles the transactions built by actions.
+//!
+//! In [`ExecutionMode::Live`] transactions are signed and broadcast. The other
+//! modes let an operator review what an action is about to do without moving
+//! funds: [`ExecutionMode::Simulate`] runs `simulateTransaction` and reports
+//! the effects, [`ExecutionMode::BuildOnly`] hands back the unsigned
+//! transaction.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::{
+    account::Account,
+    message::VersionedMessage,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::error::ActionError;
+use crate::inspect::is_token_program;
+use crate::sender::TransactionReceipt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecutionMode {
+    /// Sign, broadcast and confirm.
+    #[default]
+    Live,
+    /// Run `simulateTransaction` and report logs, compute units, fee and
+    /// balance changes. Nothing is signed by the wallet or broadcast.
+    Simulate,
+    /// Return the transaction, base64 encoded, without the wallet's signature.
+    BuildOnly,
+}
+
+/// Result of [`Agent::execute_transaction`](crate::agent::Agent::execute_transaction).
+#[derive(Debug, Clone)]
+pub enum TransactionOutcome {
+    Sent(TransactionReceipt),
+    Simulated(SimulationReport),
+    /// Base64-encoded wire transaction. Signatures of ephemeral signers (e.g.
+    /// a freshly generated mint) are filled in; the wallet's slot is left empty.
+    Built(String),
+}
+
+impl TransactionOutcome {
+    pub fn signature(&self) -> Option<&Signature> {
+        match self {
+            Self::Sent(receipt) => Some(&receipt.signature),
+            _ => None,
+        }
+    }
+
+    /// JSON returned by actions in place of their usual output when nothing was sent.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Sent(receipt) => json!({
+                "status": "success",
+                "signature": receipt.signature.to_string(),
+                "receipt": receipt,
+            }),
+            Self::Simulated(report) => json!({
+                "status": "simulated",
+                "mode": ExecutionMode::Simulate,
+                "simulation": report,
+            }),
+            Self::Built(transaction) => json!({
+                "status": "built",
+                "mode": ExecutionMode::BuildOnly,
+                "transaction": transaction,
+                "encoding": "base64",
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    /// Why the transaction would fail, if it would.
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Fee in lamports, when the RPC node could price the message.
+    pub fee: Option<u64>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub account: String,
+    pub pre: u64,
+    pub post: u64,
+    pub delta: i128,
+}
+
+/// Change in the raw (undecimalized) amount held by an SPL token account.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalanceChange {
+    pub account: String,
+    pub mint: String,
+    pub owner: String,
+    pub pre: u64,
+    pub post: u64,
+    pub delta: i128,
+}
+
+pub(crate) fn encode_transaction(tx: &VersionedTransaction) -> Result<String, ActionError> {
+    let bytes = bincode::serialize(tx)
+        .map_err(|e| ActionError::internal(format!("Failed to serialize transaction: {e}")))?;
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Simulate `tx` without signature verification and diff the writable
+/// accounts it touches.
+pub(crate) async fn simulate(client: &RpcClient, tx: &VersionedTransaction) -> Result<SimulationReport, ActionError> {
+    let keys = tx.message.static_account_keys();
+    let writable: Vec<Pubkey> = keys
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| tx.message.is_maybe_writable(*i))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let pre_accounts = client.get_multiple_accounts(&writable).await?;
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(client.commitment()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: writable.iter().map(|k| k.to_string()).collect(),
+        }),
+        ..Default::default()
+    };
+    let result = client.simulate_transaction_with_config(tx, config).await?.value;
+
+    // A failed simulation returns no account states, and so no balance changes.
+    let post_accounts: Vec<Option<Account>> = result
+        .accounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|account| account.and_then(|a| a.decode::<Account>()))
+        .collect();
+
+    let fee = match &tx.message {
+        VersionedMessage::Legacy(message) => client.get_fee_for_message(message).await,
+        VersionedMessage::V0(message) => client.get_fee_for_message(message).await,
+    }
+    .ok();
+
+    let mut balance_changes = Vec::new();
+    let mut token_balance_changes = Vec::new();
+    for ((key, pre), post) in writable.iter().zip(&pre_accounts).zip(&post_accounts) {
+        let pre_lamports = pre.as_ref().map_or(0, |a| a.lamports);
+        let post_lamports = post.as_ref().map_or(0, |a| a.lamports);
+        if pre_lamports != post_lamports {
+            balance_changes.push(BalanceChange {
+                account: key.to_string(),
+                pre: pre_lamports,
+                post: post_lamports,
+                delta: post_lamports as i128 - pre_lamports as i128,
+            });
+        }
+
+        let pre_token = pre.as_ref().and_then(token_state);
+        let post_token = post.as_ref().and_then(token_state);
+        if let Some(state) = post_token.as_ref().or(pre_token.as_ref()) {
+            let pre_amount = pre_token.as_ref().map_or(0, |s| s.amount);
+            let post_amount = post_token.as_ref().map_or(0, |s| s.amount);
+            if pre_amount != post_amount {
+                token_balance_changes.push(TokenBalanceChange {
+                    account: key.to_string(),
+                    mint: state.mint.to_string(),
+                    owner: state.owner.to_string(),
+                    pre: pre_amount,
+                    post: post_amount,
+                    delta: post_amount as i128 - pre_amount as i128,
+                });
+            }
+        }
+    }
+
+    Ok(SimulationReport {
+        err: result.err.map(|e| e.to_string()),
+        logs: result.logs.unwrap_or_default(),
+        units_consumed: result.units_consumed,
+        fee,
+        balance_changes,
+        token_balance_changes,
+    })
+}
+
+/// Token account state for accounts owned by the SPL Token or Token-2022 program.
+fn token_state(account: &Account) -> Option<spl_token::state::Account> {
+    if !is_token_program(&account.owner) || account.data.len() < spl_token::state::Account::LEN {
+        return None;
+    }
+    spl_token::state::Account::unpack_from_slice(&account.data[..spl_token::state::Account::LEN]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, VersionedMessage},
+        signature::Keypair,
+        signer::Signer,
+    };
+
+    use super::*;
+    use crate::agent::Agent;
+    use crate::wallet::KeypairWallet;
+
+    #[tokio::test]
+    async fn build_only_leaves_wallet_slot_empty() {
+        let wallet = Keypair::new();
+        let payer = wallet.pubkey();
+        let mint = Keypair::new();
+        let agent = Agent::new(Arc::new(KeypairWallet::new(wallet)), "http://localhost:8899")
+            .with_mode(ExecutionMode::BuildOnly);
+
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(payer, true), AccountMeta::new(mint.pubkey(), true)],
+        );
+        let message = v0::Message::try_compile(&payer, &[ix], &[], Hash::new_unique()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+
+        let outcome = agent.execute_transaction(tx, &[&mint]).await.unwrap();
+        let TransactionOutcome::Built(encoded) = outcome else {
+            panic!("expected a built transaction");
+        };
+        let built: VersionedTransaction = bincode::deserialize(&STANDARD.decode(encoded).unwrap()).unwrap();
+
+        assert_eq!(built.signatures.len(), 2);
+        assert_eq!(built.signatures[0], Signature::default());
+        assert!(built.signatures[1].verify(mint.pubkey().as_ref(), &built.message.serialize()));
+    }
+}
@@ -0,0 +1,528 @@
+//! Decoding of transactions into the value-moving instructions they contain.
+//!
+//! Only top-level instructions are decoded; whatever a program does through
+//! CPI (e.g. inside a Jupiter swap) is invisible here. Accounts loaded from
+//! address lookup tables cannot be resolved offline: fetch them with
+//! [`load_addresses`] and decode with [`decode_transaction_with`], or
+//! instructions that reference them decode as [`InstructionKind::Unresolved`].
+
+use std::fmt;
+
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table::state::AddressLookupTable,
+    instruction::CompiledInstruction,
+    message::v0::LoadedAddresses,
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    pubkey::Pubkey,
+    system_instruction::SystemInstruction,
+    system_program,
+    transaction::VersionedTransaction,
+};
+use spl_token::instruction::TokenInstruction;
+
+use crate::error::ActionError;
+
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Whether `program_id` is the SPL Token or Token-2022 program.
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedInstruction {
+    #[serde(serialize_with = "as_string")]
+    pub program_id: Pubkey,
+    #[serde(flatten)]
+    pub kind: InstructionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum InstructionKind {
+    /// System program transfer (plain or with seed).
+    SolTransfer {
+        #[serde(serialize_with = "as_string")]
+        from: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        to: Pubkey,
+        lamports: u64,
+    },
+    /// System program account creation (plain or with seed), funded by `from`.
+    CreateAccount {
+        #[serde(serialize_with = "as_string")]
+        from: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        new_account: Pubkey,
+        lamports: u64,
+        #[serde(serialize_with = "as_string")]
+        owner: Pubkey,
+    },
+    /// `Transfer` or `TransferChecked`; only the checked form names the mint.
+    TokenTransfer {
+        #[serde(serialize_with = "as_string")]
+        source: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        destination: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        authority: Pubkey,
+        amount: u64,
+        #[serde(serialize_with = "opt_as_string")]
+        mint: Option<Pubkey>,
+        decimals: Option<u8>,
+    },
+    /// `Approve` or `ApproveChecked`.
+    TokenApprove {
+        #[serde(serialize_with = "as_string")]
+        source: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        delegate: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        owner: Pubkey,
+        amount: u64,
+        #[serde(serialize_with = "opt_as_string")]
+        mint: Option<Pubkey>,
+        decimals: Option<u8>,
+    },
+    TokenSetAuthority {
+        #[serde(serialize_with = "as_string")]
+        account: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        current_authority: Pubkey,
+        authority_type: String,
+        #[serde(serialize_with = "opt_as_string")]
+        new_authority: Option<Pubkey>,
+    },
+    TokenCloseAccount {
+        #[serde(serialize_with = "as_string")]
+        account: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        destination: Pubkey,
+        #[serde(serialize_with = "as_string")]
+        owner: Pubkey,
+    },
+    /// A token program instruction that could not be decoded at all, such as
+    /// a Token-2022 extension instruction. It may move funds.
+    TokenUnknown { data_len: usize },
+    /// An instruction of a program (or of a kind) we do not decode.
+    Other { data_len: usize },
+    /// References accounts from an address lookup table.
+    Unresolved,
+}
+
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            InstructionKind::SolTransfer { from, to, lamports } => {
+                write!(f, "Transfer {} SOL from {from} to {to}", sol(*lamports))
+            }
+            InstructionKind::CreateAccount {
+                from,
+                new_account,
+                lamports,
+                owner,
+            } => write!(
+                f,
+                "Create account {new_account} owned by {owner}, funded with {} SOL from {from}",
+                sol(*lamports)
+            ),
+            InstructionKind::TokenTransfer {
+                source,
+                destination,
+                amount,
+                mint,
+                decimals,
+                ..
+            } => write!(
+                f,
+                "Transfer {} from token account {source} to {destination}",
+                token_amount(*amount, *mint, *decimals)
+            ),
+            InstructionKind::TokenApprove {
+                source,
+                delegate,
+                amount,
+                mint,
+                decimals,
+                ..
+            } => write!(
+                f,
+                "Allow {delegate} to spend {} from token account {source}",
+                token_amount(*amount, *mint, *decimals)
+            ),
+            InstructionKind::TokenSetAuthority {
+                account,
+                authority_type,
+                new_authority,
+                ..
+            } => match new_authority {
+                Some(new_authority) => write!(f, "Set {authority_type} authority of {account} to {new_authority}"),
+                None => write!(f, "Remove {authority_type} authority of {account}"),
+            },
+            InstructionKind::TokenCloseAccount {
+                account, destination, ..
+            } => write!(f, "Close token account {account}, sending its lamports to {destination}"),
+            InstructionKind::TokenUnknown { data_len } => write!(
+                f,
+                "Call token program {} with an instruction that could not be decoded ({data_len} bytes)",
+                self.program_id
+            ),
+            InstructionKind::Other { data_len } => {
+                write!(f, "Call program {} with {data_len} bytes of data", self.program_id)
+            }
+            InstructionKind::Unresolved => write!(
+                f,
+                "Call program {} with accounts from an address lookup table",
+                self.program_id
+            ),
+        }
+    }
+}
+
+/// One line per instruction, e.g. `Transfer 0.5 SOL from ... to ...`.
+pub fn summarize_transaction(tx: &VersionedTransaction) -> Vec<String> {
+    decode_transaction(tx).iter().map(ToString::to_string).collect()
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+fn token_amount(amount: u64, mint: Option<Pubkey>, decimals: Option<u8>) -> String {
+    match (mint, decimals) {
+        (Some(mint), Some(decimals)) => {
+            format!("{} of mint {mint}", amount as f64 / 10f64.powi(decimals as i32))
+        }
+        _ => format!("{amount} base units"),
+    }
+}
+
+/// Decode every top-level instruction of `tx`.
+pub fn decode_transaction(tx: &VersionedTransaction) -> Vec<DecodedInstruction> {
+    decode_transaction_with(tx, &LoadedAddresses::default())
+}
+
+/// Decode every top-level instruction of `tx`, resolving lookup table
+/// accounts from `loaded`.
+pub fn decode_transaction_with(tx: &VersionedTransaction, loaded: &LoadedAddresses) -> Vec<DecodedInstruction> {
+    let keys = account_keys(tx, loaded);
+    tx.message
+        .instructions()
+        .iter()
+        .map(|ix| decode_instruction(&keys, ix))
+        .collect()
+}
+
+/// The keys instructions of `tx` index into: the static keys, then the
+/// writable and read-only addresses `loaded` from lookup tables.
+pub fn account_keys(tx: &VersionedTransaction, loaded: &LoadedAddresses) -> Vec<Pubkey> {
+    let mut keys = tx.message.static_account_keys().to_vec();
+    keys.extend(&loaded.writable);
+    keys.extend(&loaded.readonly);
+    keys
+}
+
+/// The addresses `tx` loads from its lookup tables, fetched through
+/// `client`. `None` if a table does not exist or lacks an index the
+/// transaction uses; those accounts then stay unresolved.
+pub async fn load_addresses(client: &RpcClient, tx: &VersionedTransaction) -> Result<Option<LoadedAddresses>, ActionError> {
+    let Some(lookups) = tx.message.address_table_lookups().filter(|lookups| !lookups.is_empty()) else {
+        return Ok(Some(LoadedAddresses::default()));
+    };
+    let tables: Vec<Pubkey> = lookups.iter().map(|lookup| lookup.account_key).collect();
+    let accounts = client.get_multiple_accounts(&tables).await?;
+
+    let mut loaded = LoadedAddresses::default();
+    for (lookup, account) in lookups.iter().zip(&accounts) {
+        let Some(table) = account.as_ref().and_then(|account| AddressLookupTable::deserialize(&account.data).ok()) else {
+            return Ok(None);
+        };
+        let select = |indexes: &[u8]| -> Option<Vec<Pubkey>> {
+            indexes.iter().map(|i| table.addresses.get(*i as usize).copied()).collect()
+        };
+        let (Some(writable), Some(readonly)) = (select(&lookup.writable_indexes), select(&lookup.readonly_indexes)) else {
+            return Ok(None);
+        };
+        loaded.writable.extend(writable);
+        loaded.readonly.extend(readonly);
+    }
+    Ok(Some(loaded))
+}
+
+fn decode_instruction(keys: &[Pubkey], ix: &CompiledInstruction) -> DecodedInstruction {
+    let program_id = keys
+        .get(ix.program_id_index as usize)
+        .copied()
+        .unwrap_or_default();
+
+    let accounts: Option<Vec<Pubkey>> = ix
+        .accounts
+        .iter()
+        .map(|i| keys.get(*i as usize).copied())
+        .collect();
+    let Some(accounts) = accounts else {
+        return DecodedInstruction {
+            program_id,
+            kind: InstructionKind::Unresolved,
+        };
+    };
+
+    let kind = if program_id == system_program::id() {
+        decode_system(&accounts, &ix.data)
+    } else if is_token_program(&program_id) {
+        decode_token(&accounts, &ix.data)
+    } else {
+        None
+    };
+
+    DecodedInstruction {
+        program_id,
+        kind: kind.unwrap_or(InstructionKind::Other {
+            data_len: ix.data.len(),
+        }),
+    }
+}
+
+fn decode_system(accounts: &[Pubkey], data: &[u8]) -> Option<InstructionKind> {
+    let account = |i: usize| accounts.get(i).copied();
+    match bincode::deserialize::<SystemInstruction>(data).ok()? {
+        SystemInstruction::Transfer { lamports } => Some(InstructionKind::SolTransfer {
+            from: account(0)?,
+            to: account(1)?,
+            lamports,
+        }),
+        SystemInstruction::TransferWithSeed { lamports, .. } => Some(InstructionKind::SolTransfer {
+            from: account(0)?,
+            to: account(2)?,
+            lamports,
+        }),
+        SystemInstruction::CreateAccount { lamports, owner, .. }
+        | SystemInstruction::CreateAccountWithSeed { lamports, owner, .. } => {
+            Some(InstructionKind::CreateAccount {
+                from: account(0)?,
+                new_account: account(1)?,
+                lamports,
+                owner,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn decode_token(accounts: &[Pubkey], data: &[u8]) -> Option<InstructionKind> {
+    let account = |i: usize| accounts.get(i).copied();
+    let Ok(instruction) = TokenInstruction::unpack(data) else {
+        return Some(InstructionKind::TokenUnknown { data_len: data.len() });
+    };
+    match instruction {
+        TokenInstruction::Transfer { amount } => Some(InstructionKind::TokenTransfer {
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            amount,
+            mint: None,
+            decimals: None,
+        }),
+        TokenInstruction::TransferChecked { amount, decimals } => Some(InstructionKind::TokenTransfer {
+            source: account(0)?,
+            destination: account(2)?,
+            authority: account(3)?,
+            amount,
+            mint: Some(account(1)?),
+            decimals: Some(decimals),
+        }),
+        TokenInstruction::Approve { amount } => Some(InstructionKind::TokenApprove {
+            source: account(0)?,
+            delegate: account(1)?,
+            owner: account(2)?,
+            amount,
+            mint: None,
+            decimals: None,
+        }),
+        TokenInstruction::ApproveChecked { amount, decimals } => Some(InstructionKind::TokenApprove {
+            source: account(0)?,
+            delegate: account(2)?,
+            owner: account(3)?,
+            amount,
+            mint: Some(account(1)?),
+            decimals: Some(decimals),
+        }),
+        TokenInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        } => Some(InstructionKind::TokenSetAuthority {
+            account: account(0)?,
+            current_authority: account(1)?,
+            authority_type: format!("{authority_type:?}"),
+            new_authority: match new_authority {
+                COption::Some(key) => Some(key),
+                COption::None => None,
+            },
+        }),
+        TokenInstruction::CloseAccount => Some(InstructionKind::TokenCloseAccount {
+            account: account(0)?,
+            destination: account(1)?,
+            owner: account(2)?,
+        }),
+        _ => None,
+    }
+}
+
+fn as_string<S: serde::Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(key)
+}
+
+fn opt_as_string<S: serde::Serializer>(key: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error> {
+    match key {
+        Some(key) => serializer.collect_str(key),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use axum::{routing::post, Json, Router};
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde_json::{json, Value};
+    use solana_sdk::{
+        address_lookup_table::{state::LookupTableMeta, AddressLookupTableAccount},
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        system_instruction,
+    };
+
+    use super::*;
+
+    #[test]
+    fn decodes_sol_and_token_transfers() {
+        let payer = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions = vec![
+            system_instruction::transfer(&payer, &to, 42),
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &source,
+                &mint,
+                &destination,
+                &payer,
+                &[],
+                1_500_000,
+                6,
+            )
+            .unwrap(),
+        ];
+        let message = v0::Message::try_compile(&payer, &instructions, &[], Hash::default()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+
+        let decoded = decode_transaction(&tx);
+        assert_eq!(
+            decoded[0].kind,
+            InstructionKind::SolTransfer {
+                from: payer,
+                to,
+                lamports: 42
+            }
+        );
+        assert_eq!(
+            decoded[1].kind,
+            InstructionKind::TokenTransfer {
+                source,
+                destination,
+                authority: payer,
+                amount: 1_500_000,
+                mint: Some(mint),
+                decimals: Some(6),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_lookup_table_accounts() {
+        let payer = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), to],
+        };
+        let data = AddressLookupTable {
+            meta: LookupTableMeta::default(),
+            addresses: Cow::Owned(table.addresses.clone()),
+        }
+        .serialize_for_tests()
+        .unwrap();
+        let known = table.key;
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| {
+                let data = data.clone();
+                async move {
+                    let result = match request["method"].as_str().unwrap() {
+                        "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+                        "getMultipleAccounts" => {
+                            let accounts: Vec<Value> = request["params"][0]
+                                .as_array()
+                                .unwrap()
+                                .iter()
+                                .map(|key| {
+                                    if key.as_str() != Some(&known.to_string()) {
+                                        return Value::Null;
+                                    }
+                                    json!({
+                                        "data": [STANDARD.encode(&data), "base64"],
+                                        "executable": false,
+                                        "lamports": 1,
+                                        "owner": solana_sdk::address_lookup_table::program::id().to_string(),
+                                        "rentEpoch": 0,
+                                        "space": data.len(),
+                                    })
+                                })
+                                .collect();
+                            json!({ "context": { "slot": 1 }, "value": accounts })
+                        }
+                        method => panic!("unexpected method {method}"),
+                    };
+                    Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = RpcClient::new(format!("http://{}", listener.local_addr().unwrap()));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let transfer = system_instruction::transfer(&payer, &to, 42);
+        let message = v0::Message::try_compile(&payer, std::slice::from_ref(&transfer), &[table], Hash::default()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+        assert_eq!(decode_transaction(&tx)[0].kind, InstructionKind::Unresolved);
+        let loaded = load_addresses(&client, &tx).await.unwrap().unwrap();
+        assert_eq!(
+            decode_transaction_with(&tx, &loaded)[0].kind,
+            InstructionKind::SolTransfer { from: payer, to, lamports: 42 }
+        );
+
+        // A table that does not exist leaves the accounts unresolved.
+        let missing = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![to],
+        };
+        let message = v0::Message::try_compile(&payer, &[transfer], &[missing], Hash::default()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        };
+        assert!(load_addresses(&client, &tx).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,415 @@
+//! Atomic submission of several transactions as a Jito bundle.
+//!
+//! A bundle lands in a single slot with all of its transactions executed in
+//! order, or not at all. The block engine only considers bundles that pay a
+//! tip, so [`Agent::execute_transactions`](crate::agent::Agent::execute_transactions)
+//! appends a transaction that transfers [`JitoConfig::tip_lamports`] from the
+//! wallet to one of the tip accounts.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::{commitment_config::CommitmentLevel, pubkey, pubkey::Pubkey, transaction::VersionedTransaction};
+
+use crate::error::{ensure_success, ActionError};
+use crate::execution::encode_transaction;
+
+/// Bundles may hold at most five transactions, the tip transaction included.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// The mainnet tip accounts listed by `getTipAccounts`, the only ones a
+/// bundle tips. A [`Policy`](crate::policy::Policy) lets tips to them through
+/// its destination allowlist unless `allowJitoTips` is off.
+pub const TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JitoConfig {
+    pub tip_lamports: u64,
+    /// Tip account to pay, one of [`TIP_ACCOUNTS`]; picked from
+    /// `getTipAccounts` when unset.
+    pub tip_account: Option<String>,
+    pub poll_interval_ms: u64,
+    /// How long to wait for the bundle to land before giving up.
+    pub timeout_secs: u64,
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self {
+            tip_lamports: 10_000,
+            tip_account: None,
+            poll_interval_ms: 1_000,
+            timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// Not (yet) known to the block engine, e.g. dropped before an auction.
+    Invalid,
+    Pending,
+    /// Lost its auction or failed simulation; none of its transactions landed.
+    Failed,
+    Landed {
+        slot: u64,
+        /// Commitment reached so far, when the block engine reports it.
+        commitment: Option<CommitmentLevel>,
+    },
+}
+
+/// Client for the block engine's bundle JSON-RPC API.
+#[derive(Debug, Clone)]
+pub struct JitoClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl JitoClient {
+    /// `base_url` is the block engine's `/api/v1` root.
+    pub fn new(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into(),
+        }
+    }
+
+    async fn call(&self, path: &str, method: &str, params: Value) -> Result<Value, ActionError> {
+        let response = self
+            .http
+            .post(format!("{}/{path}", self.base_url))
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await?;
+        let mut body: Value = ensure_success("Jito", response).await?.json().await?;
+        if let Some(error) = body.get("error") {
+            return Err(ActionError::upstream(
+                "Jito",
+                None,
+                error["message"].as_str().unwrap_or("block engine error").to_string(),
+            ));
+        }
+        Ok(body["result"].take())
+    }
+
+    pub async fn tip_accounts(&self) -> Result<Vec<String>, ActionError> {
+        let result = self.call("bundles", "getTipAccounts", json!([])).await?;
+        serde_json::from_value(result)
+            .map_err(|e| ActionError::malformed_response("Jito", format!("invalid tip accounts: {e}")))
+    }
+
+    /// Submit signed transactions as one bundle and return its id.
+    pub async fn send_bundle(&self, txs: &[VersionedTransaction]) -> Result<String, ActionError> {
+        let encoded = txs.iter().map(encode_transaction).collect::<Result<Vec<_>, _>>()?;
+        let result = self
+            .call("bundles", "sendBundle", json!([encoded, { "encoding": "base64" }]))
+            .await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ActionError::malformed_response("Jito", "missing bundle id"))
+    }
+
+    pub async fn bundle_status(&self, bundle_id: &str) -> Result<BundleStatus, ActionError> {
+        let inflight = self
+            .call("getInflightBundleStatuses", "getInflightBundleStatuses", json!([[bundle_id]]))
+            .await?;
+        let entry = &inflight["value"][0];
+        match entry["status"].as_str() {
+            Some("Pending") => Ok(BundleStatus::Pending),
+            Some("Failed") => Ok(BundleStatus::Failed),
+            Some("Landed") => {
+                // Only getBundleStatuses reports how far the landed slot is confirmed.
+                let landed = self.call("bundles", "getBundleStatuses", json!([[bundle_id]])).await?;
+                let status = &landed["value"][0];
+                let slot = status["slot"]
+                    .as_u64()
+                    .or_else(|| entry["landed_slot"].as_u64())
+                    .unwrap_or_default();
+                let commitment = serde_json::from_value(status["confirmation_status"].clone()).ok();
+                Ok(BundleStatus::Landed { slot, commitment })
+            }
+            _ => Ok(BundleStatus::Invalid),
+        }
+    }
+
+    /// `config.tip_account`, or one of the block engine's tip accounts. Only
+    /// [`TIP_ACCOUNTS`] are paid, so a policy that lets tips through never
+    /// meets one it does not know.
+    pub(crate) async fn tip_account(&self, config: &JitoConfig) -> Result<Pubkey, ActionError> {
+        if let Some(account) = &config.tip_account {
+            let account: Pubkey = account.parse()?;
+            if !TIP_ACCOUNTS.contains(&account) {
+                return Err(ActionError::invalid_input(format!("{account} is not a Jito tip account")));
+            }
+            return Ok(account);
+        }
+        let accounts: Vec<Pubkey> = self
+            .tip_accounts()
+            .await?
+            .iter()
+            .filter_map(|account| account.parse().ok())
+            .filter(|account| TIP_ACCOUNTS.contains(account))
+            .collect();
+        if accounts.is_empty() {
+            return Err(ActionError::malformed_response("Jito", "no known tip accounts"));
+        }
+        // Spread tips across accounts to reduce write-lock contention.
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+        Ok(accounts[nanos as usize % accounts.len()])
+    }
+}
+
+/// Whether `reached` is at least as final as `target`.
+pub(crate) fn satisfies(reached: CommitmentLevel, target: CommitmentLevel) -> bool {
+    fn rank(level: CommitmentLevel) -> u8 {
+        match level {
+            CommitmentLevel::Finalized => 2,
+            CommitmentLevel::Confirmed => 1,
+            _ => 0,
+        }
+    }
+    rank(reached) >= rank(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    use axum::{extract::State, routing::post, Json, Router};
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_instruction,
+    };
+
+    use super::*;
+    use crate::actions::ActionRegistry;
+    use crate::agent::Agent;
+    use crate::compute_budget::ComputeBudgetConfig;
+    use crate::config::{AgentConfig, Provider};
+    use crate::execution::TransactionOutcome;
+    use crate::inspect::{decode_transaction, InstructionKind};
+    use crate::nft_actions::DeployCollectionAction;
+    use crate::policy::{Policy, PolicyEngine, PolicyWallet};
+    use crate::wallet::KeypairWallet;
+
+    type Bundles = Arc<Mutex<Vec<Vec<String>>>>;
+
+    /// Answers the block-engine methods the way mainnet does for a landed bundle.
+    async fn block_engine(State(bundles): State<Bundles>, Json(request): Json<Value>) -> Json<Value> {
+        let result = match request["method"].as_str().unwrap() {
+            "getTipAccounts" => json!([TIP_ACCOUNT]),
+            "sendBundle" => {
+                bundles
+                    .lock()
+                    .unwrap()
+                    .push(serde_json::from_value(request["params"][0].clone()).unwrap());
+                json!("bundle-1")
+            }
+            "getInflightBundleStatuses" => json!({
+                "value": [{ "bundle_id": "bundle-1", "status": "Landed", "landed_slot": 42 }],
+            }),
+            "getBundleStatuses" => json!({
+                "value": [{ "bundle_id": "bundle-1", "slot": 42, "confirmation_status": "confirmed" }],
+            }),
+            method => panic!("unexpected method {method}"),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    /// Just enough of a validator RPC to build transactions.
+    async fn rpc(Json(request): Json<Value>) -> Json<Value> {
+        let result = match request["method"].as_str().unwrap() {
+            "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+            "getMinimumBalanceForRentExemption" => json!(1_461_600),
+            "getLatestBlockhash" => json!({
+                "context": { "slot": 1 },
+                "value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 100 },
+            }),
+            method => panic!("unexpected method {method}"),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    const TIP_ACCOUNT: &str = "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5";
+
+    #[tokio::test]
+    async fn submits_bundle_with_tip() {
+        let bundles = Bundles::default();
+        let app = Router::new()
+            .route("/api/v1/bundles", post(block_engine))
+            .route("/api/v1/getInflightBundleStatuses", post(block_engine))
+            .with_state(bundles.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let keypair = Keypair::new();
+        let payer = keypair.pubkey();
+        let config = AgentConfig::builder()
+            .base_url(Provider::Jito, format!("http://{addr}/api/v1"))
+            .build()
+            .unwrap();
+        let agent = Agent::new(Arc::new(KeypairWallet::new(keypair)), "http://127.0.0.1:1")
+            .with_config(config)
+            .with_jito(JitoConfig {
+                poll_interval_ms: 10,
+                ..JitoConfig::default()
+            });
+
+        let txs: Vec<VersionedTransaction> = (0..2)
+            .map(|_| {
+                let ix = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1_000);
+                let message = v0::Message::try_compile(&payer, &[ix], &[], Hash::new_unique()).unwrap();
+                VersionedTransaction {
+                    signatures: vec![],
+                    message: VersionedMessage::V0(message),
+                }
+            })
+            .collect();
+
+        let outcomes = agent.execute_transactions(txs, &[], true).await.unwrap();
+        assert_eq!(outcomes.len(), 2);
+        let TransactionOutcome::Sent(receipt) = &outcomes[1] else {
+            panic!("expected a sent transaction");
+        };
+        assert_eq!(receipt.slot, 42);
+        assert_eq!(receipt.bundle_id.as_deref(), Some("bundle-1"));
+
+        let sent = bundles.lock().unwrap().pop().unwrap();
+        assert_eq!(sent.len(), 3);
+        let tip: VersionedTransaction = bincode::deserialize(&STANDARD.decode(&sent[2]).unwrap()).unwrap();
+        assert_eq!(
+            decode_transaction(&tip)[0].kind,
+            InstructionKind::SolTransfer {
+                from: payer,
+                to: TIP_ACCOUNT.parse().unwrap(),
+                lamports: JitoConfig::default().tip_lamports,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn deploys_collection_with_mints_as_one_bundle() {
+        let bundles = Bundles::default();
+        let app = Router::new()
+            .route("/api/v1/bundles", post(block_engine))
+            .route("/api/v1/getInflightBundleStatuses", post(block_engine))
+            .with_state(bundles.clone())
+            .route("/rpc", post(rpc));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut registry = ActionRegistry::new();
+        registry.register(DeployCollectionAction::new());
+        let agent = |policy: Policy| {
+            let config = AgentConfig::builder()
+                .base_url(Provider::Jito, format!("http://{addr}/api/v1"))
+                .compute_budget(ComputeBudgetConfig {
+                    enabled: false,
+                    ..ComputeBudgetConfig::default()
+                })
+                .build()
+                .unwrap();
+            // Nothing may leave the wallet except the bundle tip.
+            let engine = Arc::new(PolicyEngine::new(Policy {
+                allowed_destinations: Some(HashSet::new()),
+                ..policy
+            }));
+            let wallet = PolicyWallet::new(Arc::new(KeypairWallet::new(Keypair::new())), engine);
+            Agent::new(Arc::new(wallet), &format!("http://{addr}/rpc"))
+                .with_config(config)
+                .with_jito(JitoConfig {
+                    poll_interval_ms: 10,
+                    ..JitoConfig::default()
+                })
+        };
+        let input = json!({
+            "name": "Collection",
+            "uri": "https://example.com/collection.json",
+            "mints": [
+                { "name": "One", "uri": "https://example.com/1.json" },
+                { "name": "Two", "uri": "https://example.com/2.json" },
+            ],
+            "atomic": true,
+        });
+
+        let output = registry
+            .execute("DEPLOY_COLLECTION", &agent(Policy::default()), input.clone())
+            .await
+            .unwrap();
+        assert_eq!(output["bundleId"], "bundle-1");
+        let mints = output["mints"].as_array().unwrap();
+        assert_eq!(mints.len(), 2);
+        assert!(mints.iter().all(|mint| mint["status"] == "success"));
+
+        let sent = bundles.lock().unwrap().pop().unwrap();
+        assert_eq!(sent.len(), 4);
+        for encoded in &sent {
+            let tx: VersionedTransaction = bincode::deserialize(&STANDARD.decode(encoded).unwrap()).unwrap();
+            assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        }
+
+        let strict = Policy {
+            allow_jito_tips: false,
+            ..Policy::default()
+        };
+        let err = registry
+            .execute("DEPLOY_COLLECTION", &agent(strict), input)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ActionError::PolicyViolation { rule, .. } if rule == "allowedDestinations"));
+    }
+
+    #[tokio::test]
+    async fn only_tips_known_accounts() {
+        let unknown = Pubkey::new_unique();
+        let app = Router::new().route(
+            "/api/v1/bundles",
+            post(move |Json(request): Json<Value>| async move {
+                let result = json!([unknown.to_string(), TIP_ACCOUNT]);
+                Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let client = JitoClient::new(reqwest::Client::new(), format!("http://{addr}/api/v1"));
+
+        for _ in 0..4 {
+            let account = client.tip_account(&JitoConfig::default()).await.unwrap();
+            assert_eq!(account, TIP_ACCOUNT.parse().unwrap());
+        }
+        let config = JitoConfig {
+            tip_account: Some(unknown.to_string()),
+            ..JitoConfig::default()
+        };
+        assert!(matches!(
+            client.tip_account(&config).await,
+            Err(ActionError::InvalidInput { .. })
+        ));
+    }
+}
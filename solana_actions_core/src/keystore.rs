@@ -0,0 +1,347 @@
+//! Password-encrypted keystore files.
+//!
+//! A keystore holds one keypair encrypted with AES-256-GCM-SIV under a key
+//! stretched from the password with scrypt, so long-running agents never keep
+//! a raw secret key on disk. [`KeystoreWallet`] unlocks one at startup and
+//! signs like [`KeypairWallet`].
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "pubkey": "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+//!   "kdf": { "name": "scrypt", "logN": 15, "r": 8, "p": 1, "salt": "..." },
+//!   "cipher": { "name": "aes256gcmsiv", "nonce": "..." },
+//!   "ciphertext": "..."
+//! }
+//! ```
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use aes_gcm_siv::aead::{Aead, NewAead, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use zeroize::Zeroizing;
+
+use crate::wallet::{KeypairWallet, Wallet};
+
+const VERSION: u32 = 1;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// scrypt cost parameters, stored in the keystore so files written with
+/// different costs still open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    /// 2^15 rounds: roughly a tenth of a second and 32 MiB per unlock.
+    fn default() -> Self {
+        Self { log_n: 15, r: 8, p: 1 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfSection {
+    name: String,
+    #[serde(flatten)]
+    params: KdfParams,
+    salt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CipherSection {
+    name: String,
+    nonce: String,
+}
+
+/// The on-disk form of an encrypted keypair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keystore {
+    version: u32,
+    pubkey: String,
+    kdf: KdfSection,
+    cipher: CipherSection,
+    ciphertext: String,
+}
+
+impl Keystore {
+    /// Encrypt `keypair` under `password` with a fresh salt and nonce.
+    pub fn encrypt(keypair: &Keypair, password: &str, params: KdfParams) -> anyhow::Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let pubkey = keypair.pubkey().to_string();
+        let key = derive_key(password, &salt, params)?;
+        let secret = Zeroizing::new(keypair.to_bytes());
+        let ciphertext = Aes256GcmSiv::new(key.as_ref().into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret.as_ref(),
+                    aad: pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Encryption failed"))?;
+
+        Ok(Self {
+            version: VERSION,
+            pubkey,
+            kdf: KdfSection {
+                name: "scrypt".to_string(),
+                params,
+                salt: BASE64.encode(salt),
+            },
+            cipher: CipherSection {
+                name: "aes256gcmsiv".to_string(),
+                nonce: BASE64.encode(nonce),
+            },
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Recover the keypair. A wrong password and a tampered file fail the
+    /// same way, since the cipher cannot tell them apart.
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<Keypair> {
+        if self.version != VERSION {
+            bail!("Unsupported keystore version {}", self.version);
+        }
+        if self.kdf.name != "scrypt" || self.cipher.name != "aes256gcmsiv" {
+            bail!("Unsupported keystore scheme {}/{}", self.kdf.name, self.cipher.name);
+        }
+        let salt = BASE64.decode(&self.kdf.salt).context("Invalid keystore salt")?;
+        let nonce = BASE64.decode(&self.cipher.nonce).context("Invalid keystore nonce")?;
+        if nonce.len() != NONCE_LEN {
+            bail!("Invalid keystore nonce length {}", nonce.len());
+        }
+        let ciphertext = BASE64.decode(&self.ciphertext).context("Invalid keystore ciphertext")?;
+
+        let key = derive_key(password, &salt, self.kdf.params)?;
+        let secret = Aes256GcmSiv::new(key.as_ref().into())
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: self.pubkey.as_bytes(),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("Wrong password or corrupted keystore"))?;
+        let keypair = Keypair::from_bytes(&secret).map_err(|e| anyhow!("Invalid keypair in keystore: {e}"))?;
+        if keypair.pubkey() != self.pubkey()? {
+            bail!("Keystore public key does not match its secret key");
+        }
+        Ok(keypair)
+    }
+
+    /// The public key, readable without the password.
+    pub fn pubkey(&self) -> anyhow::Result<Pubkey> {
+        Pubkey::from_str(&self.pubkey).map_err(|e| anyhow!("Invalid keystore public key: {e}"))
+    }
+
+    pub fn kdf_params(&self) -> KdfParams {
+        self.kdf.params
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read keystore {}", path.display()))?;
+        serde_json::from_str(&contents).with_context(|| format!("Invalid keystore {}", path.display()))
+    }
+
+    /// Write to a new file, refusing to overwrite an existing one.
+    pub fn create(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let mut file = private_file(OpenOptions::new().write(true).create_new(true), path)
+            .with_context(|| format!("Failed to create keystore {}", path.display()))?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Replace the file at `path` atomically, so an interrupted write never
+    /// leaves the key unrecoverable.
+    pub fn replace(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let mut file = private_file(OpenOptions::new().write(true).create(true).truncate(true), &tmp)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace keystore {}", path.display()))
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8], params: KdfParams) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let params = scrypt::Params::new(params.log_n, params.r, params.p, 32)
+        .map_err(|e| anyhow!("Invalid scrypt parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(password.as_bytes(), salt, &params, key.as_mut())
+        .map_err(|e| anyhow!("Key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn private_file(options: &mut OpenOptions, path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn private_file(options: &mut OpenOptions, path: &Path) -> std::io::Result<std::fs::File> {
+    options.open(path)
+}
+
+/// A [`Wallet`] whose key lives in a keystore file. The decrypted keypair is
+/// held only in memory and zeroized when the wallet is dropped.
+pub struct KeystoreWallet {
+    path: PathBuf,
+    inner: KeypairWallet,
+}
+
+impl KeystoreWallet {
+    /// Decrypt the keystore at `path`.
+    pub fn unlock(path: impl Into<PathBuf>, password: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let keypair = Keystore::read(&path)?.decrypt(password)?;
+        Ok(Self {
+            path,
+            inner: KeypairWallet::new(keypair),
+        })
+    }
+
+    /// Encrypt the key of `wallet`, however it was loaded, into a new
+    /// keystore at `path`.
+    pub fn import(path: impl Into<PathBuf>, wallet: KeypairWallet, password: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        Keystore::encrypt(&wallet.keypair, password, KdfParams::default())?.create(&path)?;
+        Ok(Self { path, inner: wallet })
+    }
+
+    /// Generate a new keypair into a keystore at `path`.
+    pub fn create(path: impl Into<PathBuf>, password: &str) -> anyhow::Result<Self> {
+        Self::import(path, KeypairWallet::new(Keypair::new()), password)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Re-encrypt the file under `new_password`, keeping its KDF cost.
+    pub fn change_password(&self, old_password: &str, new_password: &str) -> anyhow::Result<()> {
+        let keystore = Keystore::read(&self.path)?;
+        let keypair = self.verified(&keystore, old_password)?;
+        Keystore::encrypt(&keypair, new_password, keystore.kdf_params())?.replace(&self.path)
+    }
+
+    /// The secret key in base58, as Phantom and Solflare import it. The
+    /// password is checked against the file again before anything is returned.
+    pub fn export(&self, password: &str) -> anyhow::Result<Zeroizing<String>> {
+        let keystore = Keystore::read(&self.path)?;
+        let keypair = self.verified(&keystore, password)?;
+        Ok(Zeroizing::new(keypair.to_base58_string()))
+    }
+
+    fn verified(&self, keystore: &Keystore, password: &str) -> anyhow::Result<Keypair> {
+        let keypair = keystore.decrypt(password)?;
+        if keypair.pubkey() != self.pubkey() {
+            bail!("Keystore {} now holds a different key", self.path.display());
+        }
+        Ok(keypair)
+    }
+}
+
+impl fmt::Debug for KeystoreWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeystoreWallet")
+            .field("path", &self.path)
+            .field("pubkey", &self.pubkey())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Wallet for KeystoreWallet {
+    fn pubkey(&self) -> Pubkey {
+        self.inner.pubkey()
+    }
+
+    async fn sign_transaction(&self, tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+        self.inner.sign_transaction(tx).await
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        txs: Vec<VersionedTransaction>,
+    ) -> anyhow::Result<Vec<VersionedTransaction>> {
+        self.inner.sign_all_transactions(txs).await
+    }
+
+    async fn sign_raw_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        self.inner.sign_raw_message(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHEAP: KdfParams = KdfParams { log_n: 4, r: 8, p: 1 };
+
+    #[tokio::test]
+    async fn round_trips_and_rotates_password() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let path = std::env::temp_dir().join(format!("solana-actions-keystore-{pubkey}.json"));
+
+        Keystore::encrypt(&keypair, "hunter2", CHEAP).unwrap().create(&path).unwrap();
+        assert!(Keystore::encrypt(&keypair, "hunter2", CHEAP).unwrap().create(&path).is_err());
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains(&keypair.to_base58_string()));
+        assert_eq!(Keystore::read(&path).unwrap().pubkey().unwrap(), pubkey);
+
+        assert!(KeystoreWallet::unlock(&path, "wrong").is_err());
+        let wallet = KeystoreWallet::unlock(&path, "hunter2").unwrap();
+        assert_eq!(wallet.pubkey(), pubkey);
+        assert!(!format!("{wallet:?}").contains(&keypair.to_base58_string()));
+
+        assert!(wallet.change_password("wrong", "correct horse").is_err());
+        wallet.change_password("hunter2", "correct horse").unwrap();
+        assert!(KeystoreWallet::unlock(&path, "hunter2").is_err());
+        assert_eq!(Keystore::read(&path).unwrap().kdf_params(), CHEAP);
+        assert!(wallet.export("hunter2").is_err());
+        assert_eq!(*wallet.export("correct horse").unwrap(), keypair.to_base58_string());
+
+        // Swapping in another key's ciphertext fails authentication.
+        let mut forged = Keystore::encrypt(&Keypair::new(), "correct horse", CHEAP).unwrap();
+        forged.pubkey = pubkey.to_string();
+        assert!(forged.decrypt("correct horse").is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
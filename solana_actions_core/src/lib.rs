@@ -1,12 +1,48 @@
 pub mod agent;
 pub mod wallet;
 pub mod actions;
+pub mod approval;
+pub mod compute_budget;
+pub mod config;
+pub mod credentials;
+pub mod error;
+pub mod execution;
+pub mod inspect;
+pub mod jito;
+pub mod keystore;
+pub mod message;
+pub mod middleware;
+pub mod policy;
+pub mod remote_signer;
+pub mod sender;
+pub mod settings;
+pub mod signing;
+pub mod siws;
+pub mod tools;
+pub mod validation;
 pub mod token_actions;
 pub mod defi_actions;
 pub mod nft_actions;
 pub mod misc_actions;
 
-pub use actions::{Action, ActionExample, ActionMetadata, ActionRegistry};
+pub use actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
+pub use approval::{ApprovalWallet, Approver, ChannelApprover, StdinApprover};
+pub use compute_budget::ComputeBudgetConfig;
+pub use config::{AgentConfig, AgentConfigBuilder, Provider};
+pub use credentials::{CredentialProvider, EnvCredentials, FileCredentials, StaticCredentials};
+pub use error::ActionError;
+pub use execution::{ExecutionMode, SimulationReport, TransactionOutcome};
+pub use jito::{BundleStatus, JitoClient, JitoConfig};
+pub use keystore::{KdfParams, Keystore, KeystoreWallet};
+pub use message::MessageFormat;
+pub use middleware::{ActionContext, Middleware, OnError, RetryMiddleware};
+pub use policy::{Policy, PolicyEngine, PolicyMiddleware, PolicyWallet};
+pub use remote_signer::RemoteSignerWallet;
+pub use sender::{SenderConfig, TransactionReceipt};
+pub use settings::Settings;
+pub use siws::SignInMessage;
+pub use tools::{ToolCall, ToolDefinition, ToolFormat, ToolSet};
+pub use validation::{FieldError, ValidationError};
 pub use token_actions::register_token_actions;
 pub use defi_actions::register_defi_actions;
 pub use nft_actions::register_nft_actions;
@@ -0,0 +1,91 @@
+//! Off-chain message signing and verification.
+//!
+//! [`Wallet::sign_message`](crate::wallet::Wallet::sign_message) wraps the
+//! message in the Solana off-chain message envelope (`\xffsolana offchain`,
+//! version 0), whose prefix can never start a transaction message, so the
+//! signature cannot be replayed on chain. Sign-In With Solana and marketplace
+//! logins instead sign the text as-is through
+//! [`Wallet::sign_raw_message`](crate::wallet::Wallet::sign_raw_message), which
+//! refuses anything that parses as a transaction message.
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{message::VersionedMessage, offchain_message::OffchainMessage, pubkey::Pubkey, signature::Signature};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageFormat {
+    /// Inside the off-chain message envelope.
+    #[default]
+    Offchain,
+    /// The bytes as given, like a wallet adapter's `signMessage`.
+    Raw,
+}
+
+/// `message` inside a version 0 off-chain message envelope: the bytes a
+/// [`MessageFormat::Offchain`] signature covers.
+pub fn offchain_envelope(message: &[u8]) -> anyhow::Result<Vec<u8>> {
+    OffchainMessage::new(0, message)
+        .and_then(|message| message.serialize())
+        .map_err(|e| anyhow!("Message cannot be signed off-chain: {e}"))
+}
+
+/// Fails if `bytes` would be accepted as a transaction message, so a raw
+/// message signature can never authorize a transaction.
+pub fn ensure_not_transaction(bytes: &[u8]) -> anyhow::Result<()> {
+    if let Ok(message) = bincode::deserialize::<VersionedMessage>(bytes) {
+        if message.sanitize().is_ok() {
+            bail!("Refusing to sign a raw message that is also a valid transaction message");
+        }
+    }
+    Ok(())
+}
+
+/// Whether `signature` is `pubkey`'s signature of `message` in `format`.
+pub fn verify_message(pubkey: &Pubkey, message: &[u8], signature: &Signature, format: MessageFormat) -> bool {
+    match format {
+        MessageFormat::Offchain => match offchain_envelope(message) {
+            Ok(envelope) => signature.verify(pubkey.as_ref(), &envelope),
+            Err(_) => false,
+        },
+        MessageFormat::Raw => signature.verify(pubkey.as_ref(), message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        message::v0,
+        signature::{Keypair, Signer},
+        system_instruction,
+    };
+
+    use super::*;
+    use crate::wallet::{KeypairWallet, Wallet};
+
+    #[tokio::test]
+    async fn signs_and_verifies_in_both_formats() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let wallet = KeypairWallet::new(keypair);
+        let text = b"Log in to Tensor";
+
+        let signature = wallet.sign_message(text).await.unwrap();
+        assert!(verify_message(&pubkey, text, &signature, MessageFormat::Offchain));
+        assert!(!verify_message(&pubkey, text, &signature, MessageFormat::Raw));
+        assert!(!verify_message(&Pubkey::new_unique(), text, &signature, MessageFormat::Offchain));
+        // Matches the reference implementation.
+        let expected = OffchainMessage::new(0, text).unwrap().sign(&*wallet.keypair).unwrap();
+        assert_eq!(signature, expected);
+
+        let raw = wallet.sign_raw_message(text).await.unwrap();
+        assert!(verify_message(&pubkey, text, &raw, MessageFormat::Raw));
+        assert!(!verify_message(&pubkey, b"Log in to Magic Eden", &raw, MessageFormat::Raw));
+
+        let ix = system_instruction::transfer(&pubkey, &Pubkey::new_unique(), 1);
+        let message = v0::Message::try_compile(&pubkey, &[ix], &[], Hash::default()).unwrap();
+        let bytes = VersionedMessage::V0(message).serialize();
+        assert!(wallet.sign_raw_message(&bytes).await.is_err());
+    }
+}
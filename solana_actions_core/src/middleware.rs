@@ -0,0 +1,285 @@
+//! Middleware run by [`ActionRegistry::execute`](crate::actions::ActionRegistry::execute)
+//! around every action call.
+//!
+//! Middleware is layered like an onion: `before` hooks run in registration
+//! order, `after` and `on_error` hooks run in reverse, so the first middleware
+//! registered is the outermost one.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::actions::ActionMetadata;
+use crate::agent::Agent;
+use crate::error::ActionError;
+
+/// Everything a hook knows about the call in flight.
+pub struct ActionContext<'a> {
+    pub name: &'a str,
+    pub metadata: &'a ActionMetadata,
+    pub agent: &'a Agent,
+    /// 1 for the first call, incremented every time a middleware asks for a retry.
+    pub attempt: u32,
+    /// When `execute` was entered, before any `before` hook ran.
+    pub started: Instant,
+    /// [`Agent::broadcast_count`] when the current attempt started.
+    pub broadcasts: u64,
+}
+
+impl ActionContext<'_> {
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Whether the agent broadcast anything since the current attempt
+    /// started. Calls on other tasks sharing the agent count too, so this
+    /// errs towards `true`.
+    pub fn broadcast_this_attempt(&self) -> bool {
+        self.agent.broadcast_count() > self.broadcasts
+    }
+}
+
+/// What should happen after an action (or a `before` hook) failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnError {
+    /// Pass the error on to the next middleware, and finally to the caller.
+    Propagate,
+    /// Call the action again with the same input. Ignored for errors raised
+    /// before the action was called (e.g. by validation or a `before` hook).
+    Retry,
+    /// Swallow the error and return this value to the caller instead.
+    Recover(Value),
+}
+
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Runs before validation and the action call. May rewrite the input, or
+    /// return an error to stop the call.
+    async fn before(&self, _ctx: &ActionContext<'_>, _input: &mut Value) -> Result<(), ActionError> {
+        Ok(())
+    }
+
+    /// Runs after a successful call. May rewrite the output, or return an error
+    /// to turn the success into a failure.
+    async fn after(
+        &self,
+        _ctx: &ActionContext<'_>,
+        _input: &Value,
+        _output: &mut Value,
+    ) -> Result<(), ActionError> {
+        Ok(())
+    }
+
+    /// Runs when validation, a `before` hook or the action itself failed. The
+    /// first middleware (innermost first) not returning [`OnError::Propagate`]
+    /// decides the outcome.
+    async fn on_error(&self, _ctx: &ActionContext<'_>, _input: &Value, _error: &ActionError) -> OnError {
+        OnError::Propagate
+    }
+}
+
+/// Retries errors that [`ActionError::is_retryable`] marks as transient, with
+/// exponential backoff. `RateLimited` errors wait for `retry_after_secs` when
+/// the provider sent one.
+///
+/// An attempt that broadcast a transaction is never retried: it may have
+/// landed even though it reported an error, and calling the action again
+/// would send it a second time.
+#[derive(Debug, Clone)]
+pub struct RetryMiddleware {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    excluded: HashSet<String>,
+}
+
+impl RetryMiddleware {
+    /// Allow up to `max_attempts` calls in total (the first call included).
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            excluded: HashSet::new(),
+        }
+    }
+
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Never retry the named actions.
+    pub fn except<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excluded.extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    fn delay(&self, attempt: u32, error: &ActionError) -> Duration {
+        if let ActionError::RateLimited {
+            retry_after_secs: Some(secs),
+            ..
+        } = error
+        {
+            return Duration::from_secs(*secs).min(self.max_delay);
+        }
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[async_trait]
+impl Middleware for RetryMiddleware {
+    async fn on_error(&self, ctx: &ActionContext<'_>, _input: &Value, error: &ActionError) -> OnError {
+        if ctx.attempt >= self.max_attempts
+            || !error.is_retryable()
+            || self.excluded.contains(ctx.name)
+            || ctx.broadcast_this_attempt()
+        {
+            return OnError::Propagate;
+        }
+        tokio::time::sleep(self.delay(ctx.attempt, error)).await;
+        OnError::Retry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        signature::{Keypair, Signature},
+        system_instruction,
+        transaction::VersionedTransaction,
+    };
+
+    use super::*;
+    use crate::actions::{Action, ActionRegistry, ActionResult};
+    use crate::wallet::KeypairWallet;
+
+    struct Flaky {
+        meta: ActionMetadata,
+        calls: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Action for Flaky {
+        fn metadata(&self) -> &ActionMetadata {
+            &self.meta
+        }
+
+        async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(ActionError::rpc("node is behind"));
+            }
+            Ok(json!({ "status": "success", "echo": input["value"] }))
+        }
+    }
+
+    /// Broadcasts a transaction to an RPC node that is not there.
+    struct Sender {
+        meta: ActionMetadata,
+        calls: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Action for Sender {
+        fn metadata(&self) -> &ActionMetadata {
+            &self.meta
+        }
+
+        async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let payer = agent.wallet.pubkey();
+            let ix = system_instruction::transfer(&payer, &payer, 1);
+            let message = v0::Message::try_compile(&payer, &[ix], &[], Hash::default()).unwrap();
+            let tx = VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::V0(message),
+            };
+            agent.send_transaction(&tx).await?;
+            Ok(json!({ "status": "success" }))
+        }
+    }
+
+    fn metadata(name: &str) -> ActionMetadata {
+        ActionMetadata {
+            name: name.to_string(),
+            similes: vec![],
+            description: String::new(),
+            examples: vec![],
+            input_schema: json!({ "type": "object" }),
+        }
+    }
+
+    struct Rewrite;
+
+    #[async_trait]
+    impl Middleware for Rewrite {
+        async fn before(&self, _ctx: &ActionContext<'_>, input: &mut Value) -> Result<(), ActionError> {
+            input["value"] = json!("rewritten");
+            Ok(())
+        }
+
+        async fn after(
+            &self,
+            ctx: &ActionContext<'_>,
+            _input: &Value,
+            output: &mut Value,
+        ) -> Result<(), ActionError> {
+            output["attempts"] = json!(ctx.attempt);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn hooks_wrap_call_and_retry() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut registry = ActionRegistry::new();
+        registry.register(Flaky {
+            meta: metadata("FLAKY"),
+            calls: calls.clone(),
+        });
+        registry.add_middleware(Rewrite);
+        registry.add_middleware(RetryMiddleware::new(2).with_backoff(Duration::ZERO, Duration::ZERO));
+
+        let agent = Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), "http://localhost:8899");
+        let output = registry
+            .execute("FLAKY", &agent, json!({ "value": "original" }))
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(output["echo"], "rewritten");
+        assert_eq!(output["attempts"], 2);
+    }
+
+    #[tokio::test]
+    async fn never_retries_after_a_broadcast() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let mut registry = ActionRegistry::new();
+        registry.register(Sender {
+            meta: metadata("SEND"),
+            calls: calls.clone(),
+        });
+        registry.add_middleware(RetryMiddleware::new(3).with_backoff(Duration::ZERO, Duration::ZERO));
+
+        let agent = Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), "http://127.0.0.1:1");
+        let err = registry.execute("SEND", &agent, json!({})).await.unwrap_err();
+
+        assert!(err.is_retryable(), "{err:?}");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(agent.broadcast_count(), 1);
+    }
+}
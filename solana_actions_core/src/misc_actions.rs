@@ -3,12 +3,16 @@
 //! Includes: CoinGecko market data, Helius transaction parsing, SNS domain resolution, and more.
 
 use async_trait::async_trait;
-use anyhow::Result;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::transaction::VersionedTransaction;
 
-use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry};
+use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
 use crate::agent::Agent;
+use crate::config::Provider;
+use crate::error::{ensure_success, ActionError};
+use crate::execution::{ExecutionMode, TransactionOutcome};
 
 // =============================================================================
 // GET_COINGECKO_TRENDING_TOKENS Action
@@ -66,18 +70,12 @@ impl Action for GetCoingeckoTrendingTokensAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, _input: Value) -> Result<Value> {
-        let url = "https://api.coingecko.com/api/v3/search/trending";
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
+        let url = format!("{}/search/trending", agent.config.base_url(Provider::CoinGecko));
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("CoinGecko API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("CoinGecko", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -149,7 +147,7 @@ impl Action for GetCoingeckoTokenInfoAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenAddress: String,
@@ -159,19 +157,13 @@ impl Action for GetCoingeckoTokenInfoAction {
 
         // Use the free CoinGecko API endpoint for Solana tokens
         let url = format!(
-            "https://api.coingecko.com/api/v3/coins/solana/contract/{}",
-            parsed.tokenAddress
+            "{}/coins/solana/contract/{}",
+            agent.config.base_url(Provider::CoinGecko), parsed.tokenAddress
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch token info: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("CoinGecko", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -249,7 +241,7 @@ impl Action for GetCoingeckoTokenPriceAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenIds: Vec<String>,
@@ -260,20 +252,14 @@ impl Action for GetCoingeckoTokenPriceAction {
         let vs_currencies = parsed.vsCurrencies.unwrap_or_else(|| vec!["usd".to_string()]);
 
         let url = format!(
-            "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies={}",
-            parsed.tokenIds.join(","),
+            "{}/simple/price?ids={}&vs_currencies={}",
+            agent.config.base_url(Provider::CoinGecko), parsed.tokenIds.join(","),
             vs_currencies.join(",")
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("CoinGecko API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("CoinGecko", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -302,10 +288,6 @@ impl ParseTransactionAction {
                     "type": "string",
                     "description": "The Solana transaction signature to parse",
                 },
-                "heliusApiKey": {
-                    "type": "string",
-                    "description": "Helius API key (optional if configured in agent)",
-                }
             },
             "required": ["transactionId"],
             "additionalProperties": false,
@@ -348,22 +330,19 @@ impl Action for ParseTransactionAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             transactionId: String,
-            heliusApiKey: Option<String>,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
 
-        let api_key = parsed.heliusApiKey.ok_or_else(|| {
-            anyhow::anyhow!("Helius API key required. Pass heliusApiKey in input.")
-        })?;
+        let api_key = agent.api_key(Provider::Helius).await?;
 
-        let url = format!("https://api.helius.xyz/v0/transactions/?api-key={}", api_key);
+        let url = format!("{}/transactions/?api-key={}", agent.config.base_url(Provider::Helius), api_key);
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(&url)
             .header("Content-Type", "application/json")
@@ -372,13 +351,7 @@ impl Action for ParseTransactionAction {
             }))
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Helius API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Helius", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -447,7 +420,7 @@ impl Action for ResolveSolDomainAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             domain: String,
@@ -460,19 +433,13 @@ impl Action for ResolveSolDomainAction {
 
         // Use Bonfida's public API
         let url = format!(
-            "https://sns-sdk-proxy.bonfida.workers.dev/resolve/{}",
-            domain_name
+            "{}/resolve/{}",
+            agent.config.base_url(Provider::Bonfida), domain_name
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to resolve domain: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Bonfida", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -483,10 +450,10 @@ impl Action for ResolveSolDomainAction {
                 "message": format!("Successfully resolved {}.sol", domain_name),
             }))
         } else {
-            Ok(json!({
-                "status": "error",
-                "message": "Domain not found or invalid response",
-            }))
+            Err(ActionError::invalid_input(format!(
+                "Domain {}.sol is not registered",
+                domain_name
+            )))
         }
     }
 }
@@ -548,7 +515,7 @@ impl Action for GetDomainForWalletAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             walletAddress: String,
@@ -558,19 +525,13 @@ impl Action for GetDomainForWalletAction {
 
         // Use Bonfida's reverse lookup API
         let url = format!(
-            "https://sns-sdk-proxy.bonfida.workers.dev/favorite-domain/{}",
-            parsed.walletAddress
+            "{}/favorite-domain/{}",
+            agent.config.base_url(Provider::Bonfida), parsed.walletAddress
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to lookup domain: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Bonfida", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -646,7 +607,7 @@ impl Action for GetCoingeckoTopGainersAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             duration: Option<String>,
@@ -656,17 +617,11 @@ impl Action for GetCoingeckoTopGainersAction {
         let _duration = parsed.duration.unwrap_or_else(|| "24h".to_string());
 
         // Use CoinGecko's coins/markets endpoint sorted by price change
-        let url = "https://api.coingecko.com/api/v3/coins/markets?vs_currency=usd&order=price_change_percentage_24h_desc&per_page=20&page=1&sparkline=false";
+        let url = format!("{}/coins/markets?vs_currency=usd&order=price_change_percentage_24h_desc&per_page=20&page=1&sparkline=false", agent.config.base_url(Provider::CoinGecko));
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("CoinGecko API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("CoinGecko", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -700,12 +655,8 @@ impl CreateHeliusWebhookAction {
                     "type": "string",
                     "description": "URL to receive webhook notifications",
                 },
-                "heliusApiKey": {
-                    "type": "string",
-                    "description": "Helius API key",
-                }
             },
-            "required": ["accountAddresses", "webhookURL", "heliusApiKey"],
+            "required": ["accountAddresses", "webhookURL"],
             "additionalProperties": false,
         });
 
@@ -713,7 +664,6 @@ impl CreateHeliusWebhookAction {
             input: json!({
                 "accountAddresses": ["86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY"],
                 "webhookURL": "https://my-server.com/webhook",
-                "heliusApiKey": "your-api-key",
             }),
             output: json!({
                 "status": "success",
@@ -746,22 +696,22 @@ impl Action for CreateHeliusWebhookAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             accountAddresses: Vec<String>,
             webhookURL: String,
-            heliusApiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
 
+        let api_key = agent.api_key(Provider::Helius).await?;
         let url = format!(
-            "https://api.helius.xyz/v0/webhooks?api-key={}",
-            parsed.heliusApiKey
+            "{}/webhooks?api-key={}",
+            agent.config.base_url(Provider::Helius), api_key
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(&url)
             .header("Content-Type", "application/json")
@@ -774,14 +724,7 @@ impl Action for CreateHeliusWebhookAction {
             }))
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to create webhook: {}", error_text),
-            }));
-        }
+        let response = ensure_success("Helius", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -812,19 +755,14 @@ impl GetHeliusWebhookAction {
                     "type": "string",
                     "description": "The webhook ID to retrieve",
                 },
-                "heliusApiKey": {
-                    "type": "string",
-                    "description": "Helius API key",
-                }
             },
-            "required": ["webhookID", "heliusApiKey"],
+            "required": ["webhookID"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "webhookID": "webhook-id-123",
-                "heliusApiKey": "your-api-key",
             }),
             output: json!({
                 "status": "success",
@@ -858,29 +796,23 @@ impl Action for GetHeliusWebhookAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             webhookID: String,
-            heliusApiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
 
+        let api_key = agent.api_key(Provider::Helius).await?;
         let url = format!(
-            "https://api.helius.xyz/v0/webhooks/{}?api-key={}",
-            parsed.webhookID, parsed.heliusApiKey
+            "{}/webhooks/{}?api-key={}",
+            agent.config.base_url(Provider::Helius), parsed.webhookID, api_key
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch webhook: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Helius", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -914,19 +846,14 @@ impl DeleteHeliusWebhookAction {
                     "type": "string",
                     "description": "The webhook ID to delete",
                 },
-                "heliusApiKey": {
-                    "type": "string",
-                    "description": "Helius API key",
-                }
             },
-            "required": ["webhookID", "heliusApiKey"],
+            "required": ["webhookID"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "webhookID": "webhook-id-123",
-                "heliusApiKey": "your-api-key",
             }),
             output: json!({
                 "status": "success",
@@ -957,29 +884,23 @@ impl Action for DeleteHeliusWebhookAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             webhookID: String,
-            heliusApiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
 
+        let api_key = agent.api_key(Provider::Helius).await?;
         let url = format!(
-            "https://api.helius.xyz/v0/webhooks/{}?api-key={}",
-            parsed.webhookID, parsed.heliusApiKey
+            "{}/webhooks/{}?api-key={}",
+            agent.config.base_url(Provider::Helius), parsed.webhookID, api_key
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.delete(&url).send().await?;
-
-        if !response.status().is_success() && response.status().as_u16() != 204 {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to delete webhook: {}", response.status()),
-            }));
-        }
+        ensure_success("Helius", response).await?;
 
         Ok(json!({
             "status": "success",
@@ -1011,12 +932,8 @@ impl SendTransactionWithPriorityAction {
                     "enum": ["low", "medium", "high", "veryHigh"],
                     "description": "Priority level for the transaction",
                 },
-                "heliusApiKey": {
-                    "type": "string",
-                    "description": "Helius API key",
-                }
             },
-            "required": ["transaction", "heliusApiKey"],
+            "required": ["transaction"],
             "additionalProperties": false,
         });
 
@@ -1024,7 +941,6 @@ impl SendTransactionWithPriorityAction {
             input: json!({
                 "transaction": "base64-encoded-tx...",
                 "priorityLevel": "high",
-                "heliusApiKey": "your-api-key",
             }),
             output: json!({
                 "status": "success",
@@ -1056,62 +972,60 @@ impl Action for SendTransactionWithPriorityAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         #[allow(dead_code)]
         struct Input {
             transaction: String,
             priorityLevel: Option<String>,
-            heliusApiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
 
+        // The transaction is already signed, so only Live mode may broadcast it.
+        match agent.mode {
+            ExecutionMode::Live => {}
+            ExecutionMode::Simulate => {
+                let tx = decode_signed_transaction(&parsed.transaction)?;
+                let report = agent.simulate_transaction(&tx).await?;
+                return Ok(TransactionOutcome::Simulated(report).to_json());
+            }
+            ExecutionMode::BuildOnly => {
+                return Ok(TransactionOutcome::Built(parsed.transaction).to_json());
+            }
+        }
+
+        let api_key = agent.api_key(Provider::Helius).await?;
         let url = format!(
-            "https://mainnet.helius-rpc.com/?api-key={}",
-            parsed.heliusApiKey
+            "{}/?api-key={}",
+            agent.config.base_url(Provider::HeliusRpc), api_key
         );
 
         let priority_level = parsed.priorityLevel.unwrap_or_else(|| "medium".to_string());
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&json!({
-                "jsonrpc": "2.0",
-                "id": "send-tx",
-                "method": "sendTransaction",
-                "params": [
-                    parsed.transaction,
-                    {
-                        "encoding": "base64",
-                        "skipPreflight": false,
-                        "preflightCommitment": "confirmed",
-                        "maxRetries": 3,
-                    }
-                ],
-            }))
-            .send()
-            .await?;
-
-        let data: Value = response.json().await?;
-
-        if let Some(error) = data.get("error") {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Transaction failed: {}", error),
-            }));
-        }
+        let tx = decode_signed_transaction(&parsed.transaction)?;
+        let helius = RpcClient::new(url);
+        let receipt = agent.send_transaction_via(&helius, &tx).await?;
 
         Ok(json!({
             "status": "success",
-            "signature": data["result"],
+            "signature": receipt.signature.to_string(),
             "priorityLevel": priority_level,
+            "receipt": receipt,
         }))
     }
 }
 
+/// Decode the base64-encoded, already signed transaction passed as input.
+fn decode_signed_transaction(input: &str) -> Result<VersionedTransaction, ActionError> {
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+    STANDARD
+        .decode(input)
+        .ok()
+        .and_then(|bytes| bincode::deserialize(&bytes).ok())
+        .ok_or_else(|| ActionError::invalid_input("transaction is not a base64-encoded transaction"))
+}
+
 // =============================================================================
 // GET_DEXSCREENER_TOKEN_PROFILES Action
 // =============================================================================
@@ -1166,20 +1080,14 @@ impl Action for GetDexscreenerTokenProfilesAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, _input: Value) -> Result<Value> {
-        let client = reqwest::Client::new();
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
+        let client = &agent.config.http;
         let response = client
-            .get("https://api.dexscreener.com/token-profiles/latest/v1")
+            .get(format!("{}/token-profiles/latest/v1", agent.config.base_url(Provider::DexScreener)))
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Dexscreener API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("DexScreener", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1251,7 +1159,7 @@ impl Action for GetDexscreenerBoostedTokensAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             #[serde(rename = "type")]
@@ -1262,24 +1170,18 @@ impl Action for GetDexscreenerBoostedTokensAction {
         let boost_type = parsed.boost_type.unwrap_or_else(|| "latest".to_string());
 
         let url = if boost_type == "top" {
-            "https://api.dexscreener.com/token-boosts/top/v1"
+            format!("{}/token-boosts/top/v1", agent.config.base_url(Provider::DexScreener))
         } else {
-            "https://api.dexscreener.com/token-boosts/latest/v1"
+            format!("{}/token-boosts/latest/v1", agent.config.base_url(Provider::DexScreener))
         };
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Dexscreener API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("DexScreener", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1356,7 +1258,7 @@ impl Action for GetDexscreenerPairsByTokenAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenAddresses: String,
@@ -1365,23 +1267,17 @@ impl Action for GetDexscreenerPairsByTokenAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://api.dexscreener.com/tokens/v1/solana/{}",
-            parsed.tokenAddresses
+            "{}/tokens/v1/solana/{}",
+            agent.config.base_url(Provider::DexScreener), parsed.tokenAddresses
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Dexscreener API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("DexScreener", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1457,7 +1353,7 @@ impl Action for SearchDexscreenerPairsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             query: String,
@@ -1466,23 +1362,17 @@ impl Action for SearchDexscreenerPairsAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://api.dexscreener.com/latest/dex/search?q={}",
-            urlencoding::encode(&parsed.query)
+            "{}/latest/dex/search?q={}",
+            agent.config.base_url(Provider::DexScreener), urlencoding::encode(&parsed.query)
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Dexscreener API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("DexScreener", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1558,7 +1448,7 @@ impl Action for GetDexscreenerPairByAddressAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             pairAddress: String,
@@ -1567,23 +1457,17 @@ impl Action for GetDexscreenerPairByAddressAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://api.dexscreener.com/latest/dex/pairs/solana/{}",
-            parsed.pairAddress
+            "{}/latest/dex/pairs/solana/{}",
+            agent.config.base_url(Provider::DexScreener), parsed.pairAddress
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Dexscreener API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("DexScreener", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1642,7 +1526,7 @@ impl Action for GetAllDomainsTldsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, _input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, _input: Value) -> ActionResult {
         // AllDomains supported TLDs - these are the main ones on Solana
         let tlds = vec![
             ".sol",      // Bonfida SNS
@@ -1715,7 +1599,7 @@ impl Action for GetOwnedDomainsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             owner: String,
@@ -1724,10 +1608,10 @@ impl Action for GetOwnedDomainsAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         // Use Bonfida SNS API to get domains
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let url = format!(
-            "https://sns-sdk-proxy.bonfida.workers.dev/domains/{}",
-            parsed.owner
+            "{}/domains/{}",
+            agent.config.base_url(Provider::Bonfida), parsed.owner
         );
 
         let response = client
@@ -1735,13 +1619,7 @@ impl Action for GetOwnedDomainsAction {
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("SNS API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Bonfida", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1807,7 +1685,7 @@ impl Action for GetFavoriteDomainAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             owner: String,
@@ -1815,10 +1693,10 @@ impl Action for GetFavoriteDomainAction {
 
         let parsed: Input = serde_json::from_value(input)?;
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let url = format!(
-            "https://sns-sdk-proxy.bonfida.workers.dev/favorite-domain/{}",
-            parsed.owner
+            "{}/favorite-domain/{}",
+            agent.config.base_url(Provider::Bonfida), parsed.owner
         );
 
         let response = client
@@ -1826,13 +1704,7 @@ impl Action for GetFavoriteDomainAction {
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("SNS API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Bonfida", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1862,19 +1734,14 @@ impl GetBirdeyeTokenOverviewAction {
                     "type": "string",
                     "description": "Token mint address",
                 },
-                "apiKey": {
-                    "type": "string",
-                    "description": "Birdeye API key",
-                }
             },
-            "required": ["address", "apiKey"],
+            "required": ["address"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "address": "So11111111111111111111111111111111111111112",
-                "apiKey": "YOUR_API_KEY"
             }),
             output: json!({
                 "status": "success",
@@ -1909,34 +1776,28 @@ impl Action for GetBirdeyeTokenOverviewAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             address: String,
-            apiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::Birdeye).await?;
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let url = format!(
-            "https://public-api.birdeye.so/defi/token_overview?address={}",
-            parsed.address
+            "{}/defi/token_overview?address={}",
+            agent.config.base_url(Provider::Birdeye), parsed.address
         );
 
         let response = client
             .get(&url)
-            .header("X-API-KEY", &parsed.apiKey)
+            .header("X-API-KEY", &api_key)
             .header("x-chain", "solana")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Birdeye API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Birdeye", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -1965,19 +1826,14 @@ impl GetBirdeyeTokenSecurityAction {
                     "type": "string",
                     "description": "Token mint address",
                 },
-                "apiKey": {
-                    "type": "string",
-                    "description": "Birdeye API key",
-                }
             },
-            "required": ["address", "apiKey"],
+            "required": ["address"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "address": "TOKEN_ADDRESS",
-                "apiKey": "YOUR_API_KEY"
             }),
             output: json!({
                 "status": "success",
@@ -2012,34 +1868,28 @@ impl Action for GetBirdeyeTokenSecurityAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             address: String,
-            apiKey: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::Birdeye).await?;
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let url = format!(
-            "https://public-api.birdeye.so/defi/token_security?address={}",
-            parsed.address
+            "{}/defi/token_security?address={}",
+            agent.config.base_url(Provider::Birdeye), parsed.address
         );
 
         let response = client
             .get(&url)
-            .header("X-API-KEY", &parsed.apiKey)
+            .header("X-API-KEY", &api_key)
             .header("x-chain", "solana")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Birdeye API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Birdeye", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -2064,10 +1914,6 @@ impl GetBirdeyeTrendingTokensAction {
         let input_schema = json!({
             "type": "object",
             "properties": {
-                "apiKey": {
-                    "type": "string",
-                    "description": "Birdeye API key",
-                },
                 "sortBy": {
                     "type": "string",
                     "enum": ["rank", "volume24hUSD", "liquidity"],
@@ -2078,13 +1924,12 @@ impl GetBirdeyeTrendingTokensAction {
                     "description": "Number of tokens to return (default: 20)",
                 }
             },
-            "required": ["apiKey"],
+            "required": [],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
-                "apiKey": "YOUR_API_KEY",
                 "sortBy": "volume24hUSD",
                 "limit": 10
             }),
@@ -2117,37 +1962,31 @@ impl Action for GetBirdeyeTrendingTokensAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
-            apiKey: String,
             sortBy: Option<String>,
             limit: Option<u32>,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::Birdeye).await?;
         let sort_by = parsed.sortBy.unwrap_or_else(|| "rank".to_string());
         let limit = parsed.limit.unwrap_or(20);
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let url = format!(
-            "https://public-api.birdeye.so/defi/tokenlist?sort_by={}&sort_type=desc&offset=0&limit={}",
-            sort_by, limit
+            "{}/defi/tokenlist?sort_by={}&sort_type=desc&offset=0&limit={}",
+            agent.config.base_url(Provider::Birdeye), sort_by, limit
         );
 
         let response = client
             .get(&url)
-            .header("X-API-KEY", &parsed.apiKey)
+            .header("X-API-KEY", &api_key)
             .header("x-chain", "solana")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Birdeye API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Birdeye", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -2176,10 +2015,6 @@ impl GetBirdeyeOhlcvAction {
                     "type": "string",
                     "description": "Token mint address",
                 },
-                "apiKey": {
-                    "type": "string",
-                    "description": "Birdeye API key",
-                },
                 "type": {
                     "type": "string",
                     "enum": ["1m", "3m", "5m", "15m", "30m", "1H", "2H", "4H", "6H", "8H", "12H", "1D", "3D", "1W", "1M"],
@@ -2194,14 +2029,13 @@ impl GetBirdeyeOhlcvAction {
                     "description": "End timestamp in seconds (optional)",
                 }
             },
-            "required": ["address", "apiKey"],
+            "required": ["address"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "address": "So11111111111111111111111111111111111111112",
-                "apiKey": "YOUR_API_KEY",
                 "type": "1H"
             }),
             output: json!({
@@ -2234,11 +2068,10 @@ impl Action for GetBirdeyeOhlcvAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             address: String,
-            apiKey: String,
             #[serde(rename = "type")]
             interval_type: Option<String>,
             timeFrom: Option<i64>,
@@ -2246,11 +2079,12 @@ impl Action for GetBirdeyeOhlcvAction {
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::Birdeye).await?;
         let interval = parsed.interval_type.unwrap_or_else(|| "1H".to_string());
 
         let mut url = format!(
-            "https://public-api.birdeye.so/defi/ohlcv?address={}&type={}",
-            parsed.address, interval
+            "{}/defi/ohlcv?address={}&type={}",
+            agent.config.base_url(Provider::Birdeye), parsed.address, interval
         );
 
         if let Some(from) = parsed.timeFrom {
@@ -2260,20 +2094,14 @@ impl Action for GetBirdeyeOhlcvAction {
             url.push_str(&format!("&time_to={}", to));
         }
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
-            .header("X-API-KEY", &parsed.apiKey)
+            .header("X-API-KEY", &api_key)
             .header("x-chain", "solana")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Birdeye API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Birdeye", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -2302,23 +2130,18 @@ impl GetBirdeyeTradesAction {
                     "type": "string",
                     "description": "Token mint address",
                 },
-                "apiKey": {
-                    "type": "string",
-                    "description": "Birdeye API key",
-                },
                 "limit": {
                     "type": "integer",
                     "description": "Number of trades to return (default: 50)",
                 }
             },
-            "required": ["address", "apiKey"],
+            "required": ["address"],
             "additionalProperties": false,
         });
 
         let examples = vec![ActionExample {
             input: json!({
                 "address": "TOKEN_ADDRESS",
-                "apiKey": "YOUR_API_KEY",
                 "limit": 20
             }),
             output: json!({
@@ -2350,36 +2173,30 @@ impl Action for GetBirdeyeTradesAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             address: String,
-            apiKey: String,
             limit: Option<u32>,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::Birdeye).await?;
         let limit = parsed.limit.unwrap_or(50);
 
         let url = format!(
-            "https://public-api.birdeye.so/defi/txs/token?address={}&limit={}",
-            parsed.address, limit
+            "{}/defi/txs/token?address={}&limit={}",
+            agent.config.base_url(Provider::Birdeye), parsed.address, limit
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(&url)
-            .header("X-API-KEY", &parsed.apiKey)
+            .header("X-API-KEY", &api_key)
             .header("x-chain", "solana")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Birdeye API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Birdeye", response).await?;
 
         let data: Value = response.json().await?;
 
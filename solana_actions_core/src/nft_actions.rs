@@ -3,14 +3,15 @@
 //! Includes: Metaplex DAS API, MagicEden marketplace, Tensor trade.
 
 use async_trait::async_trait;
-use anyhow::Result;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry};
+use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
 use crate::agent::Agent;
-
-const MAGIC_EDEN_API_URL: &str = "https://api-mainnet.magiceden.dev/v2";
+use crate::config::Provider;
+use crate::error::{ensure_success, ActionError};
+use crate::execution::TransactionOutcome;
+use crate::sender::TransactionReceipt;
 
 // =============================================================================
 // GET_ASSET Action (Metaplex DAS API)
@@ -74,7 +75,7 @@ impl Action for GetAssetAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             assetId: String,
@@ -95,21 +96,19 @@ impl Action for GetAssetAction {
             },
         });
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(rpc_url)
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
             .await?;
+        let response = ensure_success("RPC", response).await?;
 
         let data: Value = response.json().await?;
 
         if let Some(error) = data.get("error") {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("DAS API error: {}", error),
-            }));
+            return Err(ActionError::rpc(format!("DAS API error: {}", error)));
         }
 
         Ok(json!({
@@ -188,7 +187,7 @@ impl Action for GetMagicEdenCollectionStatsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             collectionSymbol: String,
@@ -200,18 +199,12 @@ impl Action for GetMagicEdenCollectionStatsAction {
 
         let url = format!(
             "{}/collections/{}/stats?timeWindow={}",
-            MAGIC_EDEN_API_URL, parsed.collectionSymbol, time_window
+            agent.config.base_url(Provider::MagicEden), parsed.collectionSymbol, time_window
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch collection stats: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Magic Eden", response).await?;
 
         let stats: Value = response.json().await?;
 
@@ -285,7 +278,7 @@ impl Action for GetPopularMagicEdenCollectionsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             timeRange: Option<String>,
@@ -296,18 +289,12 @@ impl Action for GetPopularMagicEdenCollectionsAction {
 
         let url = format!(
             "{}/marketplace/popular_collections?timeRange={}",
-            MAGIC_EDEN_API_URL, time_range
+            agent.config.base_url(Provider::MagicEden), time_range
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch popular collections: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Magic Eden", response).await?;
 
         let collections: Value = response.json().await?;
 
@@ -391,7 +378,7 @@ impl Action for GetMagicEdenCollectionListingsAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             collectionSymbol: String,
@@ -405,18 +392,12 @@ impl Action for GetMagicEdenCollectionListingsAction {
 
         let url = format!(
             "{}/collections/{}/listings?limit={}&offset={}",
-            MAGIC_EDEN_API_URL, parsed.collectionSymbol, limit, offset
+            agent.config.base_url(Provider::MagicEden), parsed.collectionSymbol, limit, offset
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch listings: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Magic Eden", response).await?;
 
         let listings: Value = response.json().await?;
 
@@ -507,7 +488,7 @@ impl Action for SearchAssetsAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         #[allow(dead_code)]
         struct Input {
@@ -546,21 +527,19 @@ impl Action for SearchAssetsAction {
             "params": params,
         });
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(rpc_url)
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
             .await?;
+        let response = ensure_success("RPC", response).await?;
 
         let data: Value = response.json().await?;
 
         if let Some(error) = data.get("error") {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("DAS API error: {}", error),
-            }));
+            return Err(ActionError::rpc(format!("DAS API error: {}", error)));
         }
 
         Ok(json!({
@@ -643,7 +622,7 @@ impl Action for GetAssetsByCreatorAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             creator: String,
@@ -667,21 +646,19 @@ impl Action for GetAssetsByCreatorAction {
             }
         });
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(rpc_url)
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
             .await?;
+        let response = ensure_success("RPC", response).await?;
 
         let data: Value = response.json().await?;
 
         if let Some(error) = data.get("error") {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("DAS API error: {}", error),
-            }));
+            return Err(ActionError::rpc(format!("DAS API error: {}", error)));
         }
 
         Ok(json!({
@@ -767,7 +744,7 @@ impl Action for GetAssetsByAuthorityAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             authority: String,
@@ -800,21 +777,19 @@ impl Action for GetAssetsByAuthorityAction {
             "params": params,
         });
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(rpc_url)
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
             .await?;
+        let response = ensure_success("RPC", response).await?;
 
         let data: Value = response.json().await?;
 
         if let Some(error) = data.get("error") {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("DAS API error: {}", error),
-            }));
+            return Err(ActionError::rpc(format!("DAS API error: {}", error)));
         }
 
         Ok(json!({
@@ -825,6 +800,107 @@ impl Action for GetAssetsByAuthorityAction {
     }
 }
 
+// =============================================================================
+// Metaplex NFT creation shared by DEPLOY_COLLECTION and MINT_NFT
+// =============================================================================
+
+/// Accounts of an NFT created by [`nft_instructions`].
+struct NewNft {
+    /// Freshly generated; must sign the transaction.
+    mint: solana_sdk::signature::Keypair,
+    metadata: solana_sdk::pubkey::Pubkey,
+    master_edition: solana_sdk::pubkey::Pubkey,
+    token_account: solana_sdk::pubkey::Pubkey,
+}
+
+/// Instructions that create a new 0-decimal mint, mint its single token to
+/// `owner` and add Metaplex metadata and a master edition. The wallet pays
+/// and keeps the mint, freeze and update authorities.
+async fn nft_instructions(
+    agent: &Agent,
+    owner: &solana_sdk::pubkey::Pubkey,
+    data: mpl_token_metadata::types::DataV2,
+    collection_details: Option<mpl_token_metadata::types::CollectionDetails>,
+) -> Result<(NewNft, Vec<solana_sdk::instruction::Instruction>), ActionError> {
+    use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+    use mpl_token_metadata::instructions::{
+        CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs, CreateMetadataAccountV3,
+        CreateMetadataAccountV3InstructionArgs,
+    };
+    use solana_sdk::program_pack::Pack;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+    use solana_sdk::system_instruction;
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::instruction as token_instruction;
+
+    let payer = agent.wallet.pubkey();
+    let mint = Keypair::new();
+    let mint_pubkey = mint.pubkey();
+    let mint_rent = agent
+        .client
+        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+        .await?;
+    let token_account = get_associated_token_address(owner, &mint_pubkey);
+    let (metadata, _) = Metadata::find_pda(&mint_pubkey);
+    let (master_edition, _) = MasterEdition::find_pda(&mint_pubkey);
+
+    let instructions = vec![
+        system_instruction::create_account(
+            &payer,
+            &mint_pubkey,
+            mint_rent,
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        token_instruction::initialize_mint(&spl_token::id(), &mint_pubkey, &payer, Some(&payer), 0)?,
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &payer,
+            owner,
+            &mint_pubkey,
+            &spl_token::id(),
+        ),
+        token_instruction::mint_to(&spl_token::id(), &mint_pubkey, &token_account, &payer, &[], 1)?,
+        CreateMetadataAccountV3 {
+            metadata,
+            mint: mint_pubkey,
+            mint_authority: payer,
+            payer,
+            update_authority: (payer, true),
+            system_program: solana_sdk::system_program::id(),
+            rent: None,
+        }
+        .instruction(CreateMetadataAccountV3InstructionArgs {
+            data,
+            is_mutable: true,
+            collection_details,
+        }),
+        // A max supply of 0 makes it a one of one.
+        CreateMasterEditionV3 {
+            edition: master_edition,
+            mint: mint_pubkey,
+            update_authority: payer,
+            mint_authority: payer,
+            payer,
+            metadata,
+            token_program: spl_token::id(),
+            system_program: solana_sdk::system_program::id(),
+            rent: None,
+        }
+        .instruction(CreateMasterEditionV3InstructionArgs { max_supply: Some(0) }),
+    ];
+
+    Ok((
+        NewNft {
+            mint,
+            metadata,
+            master_edition,
+            token_account,
+        },
+        instructions,
+    ))
+}
+
 // =============================================================================
 // DEPLOY_COLLECTION Action (Metaplex)
 // =============================================================================
@@ -847,28 +923,66 @@ impl DeployCollectionAction {
                     "type": "string",
                     "description": "Metadata URI for the collection (must be a valid URL)",
                 },
+                "symbol": {
+                    "type": "string",
+                    "description": "Symbol for the collection (optional)",
+                },
                 "royaltyBasisPoints": {
                     "type": "integer",
                     "description": "Royalty in basis points (100 = 1%)",
+                },
+                "mints": {
+                    "type": "array",
+                    "description": "NFTs to mint into the new collection, one transaction each (optional)",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "uri": { "type": "string" },
+                        },
+                        "required": ["name", "uri"],
+                        "additionalProperties": false,
+                    },
+                },
+                "atomic": {
+                    "type": "boolean",
+                    "description": "Land the collection and its mints together or not at all, as a Jito bundle (at most 3 mints)",
                 }
             },
             "required": ["name", "uri"],
             "additionalProperties": false,
         });
 
-        let examples = vec![ActionExample {
-            input: json!({
-                "name": "My Collection",
-                "uri": "https://example.com/collection.json",
-                "royaltyBasisPoints": 500,
-            }),
-            output: json!({
-                "status": "success",
-                "collectionAddress": "7nE9Gvc...",
-                "name": "My Collection",
-            }),
-            explanation: "Deploy an NFT collection with 5% royalty".to_string(),
-        }];
+        let examples = vec![
+            ActionExample {
+                input: json!({
+                    "name": "My Collection",
+                    "uri": "https://example.com/collection.json",
+                    "symbol": "MYC",
+                    "royaltyBasisPoints": 500,
+                }),
+                output: json!({
+                    "status": "success",
+                    "collectionAddress": "7nE9Gvc...",
+                    "name": "My Collection",
+                }),
+                explanation: "Deploy an NFT collection with 5% royalty".to_string(),
+            },
+            ActionExample {
+                input: json!({
+                    "name": "My Collection",
+                    "uri": "https://example.com/collection.json",
+                    "mints": [{ "name": "My NFT #1", "uri": "https://example.com/nft1.json" }],
+                    "atomic": true,
+                }),
+                output: json!({
+                    "status": "success",
+                    "collectionAddress": "7nE9Gvc...",
+                    "mints": [{ "status": "success", "mint": "8mF0Hwd...", "signature": "5xY2Abc..." }],
+                }),
+                explanation: "Deploy a collection and its first NFT in one Jito bundle".to_string(),
+            },
+        ];
 
         let meta = ActionMetadata {
             name: "DEPLOY_COLLECTION".to_string(),
@@ -893,22 +1007,9 @@ impl Action for DeployCollectionAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
-        use mpl_token_metadata::instructions::{
-            CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
-            CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs,
-        };
-        use mpl_token_metadata::types::{DataV2, Creator};
-        use solana_sdk::pubkey::Pubkey;
-        use solana_sdk::signature::Keypair;
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
+        use mpl_token_metadata::types::{Collection, CollectionDetails, Creator, DataV2};
         use solana_sdk::signer::Signer;
-        use solana_sdk::system_instruction;
-        use solana_sdk::instruction::Instruction;
-        use solana_sdk::message::{self, VersionedMessage};
-        use solana_sdk::transaction::VersionedTransaction;
-        use solana_sdk::program_pack::Pack;
-        use spl_token::instruction as token_instruction;
-        use spl_associated_token_account::get_associated_token_address;
 
         #[derive(Deserialize)]
         #[allow(dead_code)]
@@ -917,175 +1018,106 @@ impl Action for DeployCollectionAction {
             uri: String,
             symbol: Option<String>,
             royaltyBasisPoints: Option<u16>,
+            #[serde(default)]
+            mints: Vec<MintInput>,
+            #[serde(default)]
+            atomic: bool,
+        }
+
+        #[derive(Deserialize)]
+        struct MintInput {
+            name: String,
+            uri: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
         let payer = agent.wallet.pubkey();
         let symbol = parsed.symbol.unwrap_or_else(|| "".to_string());
         let royalty_basis_points = parsed.royaltyBasisPoints.unwrap_or(500); // Default 5%
-
-        // Generate a new keypair for the collection mint
-        let mint_keypair = Keypair::new();
-        let mint_pubkey = mint_keypair.pubkey();
-
-        // Calculate rent for mint account
-        let mint_rent = agent.client.get_minimum_balance_for_rent_exemption(
-            spl_token::state::Mint::LEN
-        )?;
-
-        let mut instructions: Vec<Instruction> = Vec::new();
-
-        // 1. Create mint account
-        instructions.push(system_instruction::create_account(
-            &payer,
-            &mint_pubkey,
-            mint_rent,
-            spl_token::state::Mint::LEN as u64,
-            &spl_token::id(),
-        ));
-
-        // 2. Initialize mint (0 decimals for NFT)
-        instructions.push(token_instruction::initialize_mint(
-            &spl_token::id(),
-            &mint_pubkey,
-            &payer,        // mint authority
-            Some(&payer),  // freeze authority
-            0,             // 0 decimals for NFT
-        )?);
-
-        // 3. Create associated token account
-        let ata = get_associated_token_address(&payer, &mint_pubkey);
-        instructions.push(
-            spl_associated_token_account::instruction::create_associated_token_account(
-                &payer,
-                &payer,
-                &mint_pubkey,
-                &spl_token::id(),
-            )
-        );
-
-        // 4. Mint 1 token (the collection NFT)
-        instructions.push(token_instruction::mint_to(
-            &spl_token::id(),
-            &mint_pubkey,
-            &ata,
-            &payer,
-            &[],
-            1,
-        )?);
-
-        // 5. Create metadata account
-        let metadata_seeds = &[
-            b"metadata",
-            mpl_token_metadata::ID.as_ref(),
-            mint_pubkey.as_ref(),
-        ];
-        let (metadata_pda, _) = Pubkey::find_program_address(
-            metadata_seeds,
-            &mpl_token_metadata::ID,
-        );
-
-        let create_metadata_ix = CreateMetadataAccountV3 {
-            metadata: metadata_pda,
-            mint: mint_pubkey,
-            mint_authority: payer,
-            payer,
-            update_authority: (payer, true),
-            system_program: solana_sdk::system_program::id(),
-            rent: None,
-        };
-
-        let metadata_args = CreateMetadataAccountV3InstructionArgs {
-            data: DataV2 {
-                name: parsed.name.clone(),
-                symbol: symbol.clone(),
-                uri: parsed.uri.clone(),
-                seller_fee_basis_points: royalty_basis_points,
-                creators: Some(vec![Creator {
-                    address: payer,
-                    verified: true,
-                    share: 100,
-                }]),
-                collection: None,
-                uses: None,
-            },
-            is_mutable: true,
-            collection_details: Some(mpl_token_metadata::types::CollectionDetails::V1 { size: 0 }),
-        };
-
-        instructions.push(create_metadata_ix.instruction(metadata_args));
-
-        // 6. Create master edition (makes it a proper NFT with limited supply of 1)
-        let edition_seeds = &[
-            b"metadata",
-            mpl_token_metadata::ID.as_ref(),
-            mint_pubkey.as_ref(),
-            b"edition",
-        ];
-        let (edition_pda, _) = Pubkey::find_program_address(
-            edition_seeds,
-            &mpl_token_metadata::ID,
-        );
-
-        let create_edition_ix = CreateMasterEditionV3 {
-            edition: edition_pda,
-            mint: mint_pubkey,
-            update_authority: payer,
-            mint_authority: payer,
-            payer,
-            metadata: metadata_pda,
-            token_program: spl_token::id(),
-            system_program: solana_sdk::system_program::id(),
-            rent: None,
+        let data = |name: String, uri: String, collection: Option<Collection>| DataV2 {
+            name,
+            symbol: symbol.clone(),
+            uri,
+            seller_fee_basis_points: royalty_basis_points,
+            creators: Some(vec![Creator {
+                address: payer,
+                verified: true,
+                share: 100,
+            }]),
+            collection,
+            uses: None,
         };
 
-        let edition_args = CreateMasterEditionV3InstructionArgs {
-            max_supply: Some(0), // 0 means unlimited editions can be printed (for collection)
-        };
-
-        instructions.push(create_edition_ix.instruction(edition_args));
-
-        // Build and sign transaction
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        let message = VersionedMessage::V0(message::v0::Message::try_compile(
+        // The collection NFT; a size of 0 marks it as a sized collection.
+        let (collection, instructions) = nft_instructions(
+            agent,
             &payer,
-            &instructions,
-            &[],
-            latest_blockhash,
-        )?);
-
-        let tx = VersionedTransaction {
-            signatures: vec![],
-            message,
-        };
-
-        // Sign with mint keypair
-        let message_bytes = tx.message.serialize();
-        let mint_sig = mint_keypair.sign_message(&message_bytes);
-        
-        // Sign with wallet
-        let signed_tx = agent.wallet.sign_transaction(tx).await?;
-        
-        // Add mint signature
-        let mut final_tx = signed_tx;
-        if final_tx.signatures.len() >= 2 {
-            final_tx.signatures[1] = mint_sig;
-        } else {
-            final_tx.signatures.push(mint_sig);
+            data(parsed.name.clone(), parsed.uri.clone(), None),
+            Some(CollectionDetails::V1 { size: 0 }),
+        )
+        .await?;
+        let mut txs = vec![agent.build_transaction(&instructions).await?];
+
+        // Each NFT in its own transaction, pointing at the new collection.
+        // The collection authority still has to verify them.
+        let mut nfts = Vec::with_capacity(parsed.mints.len());
+        for item in parsed.mints {
+            let reference = Collection {
+                verified: false,
+                key: collection.mint.pubkey(),
+            };
+            let (nft, instructions) = nft_instructions(agent, &payer, data(item.name, item.uri, Some(reference)), None).await?;
+            txs.push(agent.build_transaction(&instructions).await?);
+            nfts.push(nft);
         }
 
-        let signature = agent.client.send_and_confirm_transaction(&final_tx)?;
+        let signers: Vec<&(dyn Signer + Sync)> = std::iter::once(&collection)
+            .chain(&nfts)
+            .map(|nft| &nft.mint as &(dyn Signer + Sync))
+            .collect();
+        let outcomes = agent.execute_transactions(txs, &signers, parsed.atomic).await?;
 
-        Ok(json!({
-            "status": "success",
-            "collectionAddress": mint_pubkey.to_string(),
-            "metadata": metadata_pda.to_string(),
-            "masterEdition": edition_pda.to_string(),
-            "signature": signature.to_string(),
-            "name": parsed.name,
-            "symbol": symbol,
-            "royaltyBasisPoints": royalty_basis_points,
-        }))
+        let mut output = match &outcomes[0] {
+            TransactionOutcome::Sent(receipt) => json!({
+                "status": "success",
+                "collectionAddress": collection.mint.pubkey().to_string(),
+                "metadata": collection.metadata.to_string(),
+                "masterEdition": collection.master_edition.to_string(),
+                "signature": receipt.signature.to_string(),
+                "name": parsed.name,
+                "symbol": symbol,
+                "royaltyBasisPoints": royalty_basis_points,
+            }),
+            preview => {
+                let mut output = preview.to_json();
+                output["collectionAddress"] = json!(collection.mint.pubkey().to_string());
+                output["metadata"] = json!(collection.metadata.to_string());
+                output["masterEdition"] = json!(collection.master_edition.to_string());
+                output
+            }
+        };
+        if let TransactionOutcome::Sent(TransactionReceipt {
+            bundle_id: Some(bundle_id),
+            ..
+        }) = &outcomes[0]
+        {
+            output["bundleId"] = json!(bundle_id);
+        }
+        if !nfts.is_empty() {
+            let mints: Vec<Value> = nfts
+                .iter()
+                .zip(&outcomes[1..])
+                .map(|(nft, outcome)| {
+                    let mut minted = outcome.to_json();
+                    minted["mint"] = json!(nft.mint.pubkey().to_string());
+                    minted["metadata"] = json!(nft.metadata.to_string());
+                    minted["masterEdition"] = json!(nft.master_edition.to_string());
+                    minted
+                })
+                .collect();
+            output["mints"] = json!(mints);
+        }
+        Ok(output)
     }
 }
 
@@ -1171,22 +1203,10 @@ impl Action for MintNftAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
-        use mpl_token_metadata::instructions::{
-            CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
-            CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs,
-        };
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         use mpl_token_metadata::types::{DataV2, Creator, Collection};
         use solana_sdk::pubkey::Pubkey;
-        use solana_sdk::signature::Keypair;
         use solana_sdk::signer::Signer;
-        use solana_sdk::system_instruction;
-        use solana_sdk::instruction::Instruction;
-        use solana_sdk::message::{self, VersionedMessage};
-        use solana_sdk::transaction::VersionedTransaction;
-        use solana_sdk::program_pack::Pack;
-        use spl_token::instruction as token_instruction;
-        use spl_associated_token_account::get_associated_token_address;
         use std::str::FromStr;
 
         #[derive(Deserialize)]
@@ -1207,7 +1227,7 @@ impl Action for MintNftAction {
         
         // Determine recipient (default to payer)
         let recipient = if let Some(ref addr) = parsed.recipient {
-            Pubkey::from_str(addr).map_err(|e| anyhow::anyhow!("Invalid recipient address: {}", e))?
+            Pubkey::from_str(addr).map_err(|e| ActionError::invalid_input(format!("Invalid recipient address: {}", e)))?
         } else {
             payer
         };
@@ -1215,7 +1235,7 @@ impl Action for MintNftAction {
         // Parse collection mint if provided
         let collection = if let Some(ref coll_mint) = parsed.collectionMint {
             let coll_pubkey = Pubkey::from_str(coll_mint)
-                .map_err(|e| anyhow::anyhow!("Invalid collection mint: {}", e))?;
+                .map_err(|e| ActionError::invalid_input(format!("Invalid collection mint: {}", e)))?;
             Some(Collection {
                 verified: false, // Will need to be verified separately by collection authority
                 key: coll_pubkey,
@@ -1224,164 +1244,41 @@ impl Action for MintNftAction {
             None
         };
 
-        // Generate a new keypair for the NFT mint
-        let mint_keypair = Keypair::new();
-        let mint_pubkey = mint_keypair.pubkey();
-
-        // Calculate rent for mint account
-        let mint_rent = agent.client.get_minimum_balance_for_rent_exemption(
-            spl_token::state::Mint::LEN
-        )?;
-
-        let mut instructions: Vec<Instruction> = Vec::new();
-
-        // 1. Create mint account
-        instructions.push(system_instruction::create_account(
-            &payer,
-            &mint_pubkey,
-            mint_rent,
-            spl_token::state::Mint::LEN as u64,
-            &spl_token::id(),
-        ));
-
-        // 2. Initialize mint (0 decimals for NFT)
-        instructions.push(token_instruction::initialize_mint(
-            &spl_token::id(),
-            &mint_pubkey,
-            &payer,        // mint authority
-            Some(&payer),  // freeze authority
-            0,             // 0 decimals for NFT
-        )?);
-
-        // 3. Create associated token account for recipient
-        let ata = get_associated_token_address(&recipient, &mint_pubkey);
-        instructions.push(
-            spl_associated_token_account::instruction::create_associated_token_account(
-                &payer,
-                &recipient,
-                &mint_pubkey,
-                &spl_token::id(),
-            )
-        );
-
-        // 4. Mint 1 token (the NFT)
-        instructions.push(token_instruction::mint_to(
-            &spl_token::id(),
-            &mint_pubkey,
-            &ata,
-            &payer,
-            &[],
-            1,
-        )?);
-
-        // 5. Create metadata account
-        let metadata_seeds = &[
-            b"metadata",
-            mpl_token_metadata::ID.as_ref(),
-            mint_pubkey.as_ref(),
-        ];
-        let (metadata_pda, _) = Pubkey::find_program_address(
-            metadata_seeds,
-            &mpl_token_metadata::ID,
-        );
-
-        let create_metadata_ix = CreateMetadataAccountV3 {
-            metadata: metadata_pda,
-            mint: mint_pubkey,
-            mint_authority: payer,
-            payer,
-            update_authority: (payer, true),
-            system_program: solana_sdk::system_program::id(),
-            rent: None,
-        };
-
-        let metadata_args = CreateMetadataAccountV3InstructionArgs {
-            data: DataV2 {
-                name: parsed.name.clone(),
-                symbol: symbol.clone(),
-                uri: parsed.uri.clone(),
-                seller_fee_basis_points,
-                creators: Some(vec![Creator {
-                    address: payer,
-                    verified: true,
-                    share: 100,
-                }]),
-                collection,
-                uses: None,
-            },
-            is_mutable: true,
-            collection_details: None,
-        };
-
-        instructions.push(create_metadata_ix.instruction(metadata_args));
-
-        // 6. Create master edition (makes it a proper NFT with supply of 1)
-        let edition_seeds = &[
-            b"metadata",
-            mpl_token_metadata::ID.as_ref(),
-            mint_pubkey.as_ref(),
-            b"edition",
-        ];
-        let (edition_pda, _) = Pubkey::find_program_address(
-            edition_seeds,
-            &mpl_token_metadata::ID,
-        );
-
-        let create_edition_ix = CreateMasterEditionV3 {
-            edition: edition_pda,
-            mint: mint_pubkey,
-            update_authority: payer,
-            mint_authority: payer,
-            payer,
-            metadata: metadata_pda,
-            token_program: spl_token::id(),
-            system_program: solana_sdk::system_program::id(),
-            rent: None,
-        };
-
-        let edition_args = CreateMasterEditionV3InstructionArgs {
-            max_supply: Some(0), // 0 = non-fungible (only 1 can exist)
+        let data = DataV2 {
+            name: parsed.name.clone(),
+            symbol: symbol.clone(),
+            uri: parsed.uri.clone(),
+            seller_fee_basis_points,
+            creators: Some(vec![Creator {
+                address: payer,
+                verified: true,
+                share: 100,
+            }]),
+            collection,
+            uses: None,
         };
-
-        instructions.push(create_edition_ix.instruction(edition_args));
-
-        // Build and sign transaction
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        let message = VersionedMessage::V0(message::v0::Message::try_compile(
-            &payer,
-            &instructions,
-            &[],
-            latest_blockhash,
-        )?);
-
-        let tx = VersionedTransaction {
-            signatures: vec![],
-            message,
+        let (nft, instructions) = nft_instructions(agent, &recipient, data, None).await?;
+        let tx = agent.build_transaction(&instructions).await?;
+
+        // Sign with the wallet and the new mint keypair
+        let outcome = agent.execute_transaction(tx, &[&nft.mint]).await?;
+        let signature = match outcome {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => {
+                let mut output = preview.to_json();
+                output["mint"] = json!(nft.mint.pubkey().to_string());
+                output["metadata"] = json!(nft.metadata.to_string());
+                output["masterEdition"] = json!(nft.master_edition.to_string());
+                return Ok(output);
+            }
         };
 
-        // Sign with mint keypair
-        let message_bytes = tx.message.serialize();
-        let mint_sig = mint_keypair.sign_message(&message_bytes);
-        
-        // Sign with wallet
-        let signed_tx = agent.wallet.sign_transaction(tx).await?;
-        
-        // Add mint signature
-        let mut final_tx = signed_tx;
-        if final_tx.signatures.len() >= 2 {
-            final_tx.signatures[1] = mint_sig;
-        } else {
-            final_tx.signatures.push(mint_sig);
-        }
-
-        let signature = agent.client.send_and_confirm_transaction(&final_tx)?;
-
         Ok(json!({
             "status": "success",
-            "mint": mint_pubkey.to_string(),
-            "metadata": metadata_pda.to_string(),
-            "masterEdition": edition_pda.to_string(),
-            "tokenAccount": ata.to_string(),
+            "mint": nft.mint.pubkey().to_string(),
+            "metadata": nft.metadata.to_string(),
+            "masterEdition": nft.master_edition.to_string(),
+            "tokenAccount": nft.token_account.to_string(),
             "recipient": recipient.to_string(),
             "signature": signature.to_string(),
             "name": parsed.name,
@@ -1453,7 +1350,7 @@ impl Action for ListNftForSaleAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             nftMint: String,
@@ -1537,7 +1434,7 @@ impl Action for CancelNftListingAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             nftMint: String,
@@ -1581,16 +1478,12 @@ impl ListMagicEdenNftAction {
                     "type": "number",
                     "description": "Price in SOL",
                 },
-                "magicEdenApiKey": {
-                    "type": "string",
-                    "description": "MagicEden API key for authentication",
-                },
                 "auctionHouseAddress": {
                     "type": "string",
                     "description": "Optional auction house address",
                 }
             },
-            "required": ["tokenMint", "tokenAccount", "price", "magicEdenApiKey"],
+            "required": ["tokenMint", "tokenAccount", "price"],
             "additionalProperties": false,
         });
 
@@ -1599,7 +1492,6 @@ impl ListMagicEdenNftAction {
                 "tokenMint": "TOKEN_MINT_ADDRESS",
                 "tokenAccount": "TOKEN_ACCOUNT_ADDRESS",
                 "price": 1.5,
-                "magicEdenApiKey": "YOUR_API_KEY",
             }),
             output: json!({
                 "status": "success",
@@ -1631,7 +1523,7 @@ impl Action for ListMagicEdenNftAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         use solana_sdk::transaction::VersionedTransaction;
 
         #[derive(Deserialize)]
@@ -1640,18 +1532,18 @@ impl Action for ListMagicEdenNftAction {
             tokenMint: String,
             tokenAccount: String,
             price: f64,
-            magicEdenApiKey: String,
             auctionHouseAddress: Option<String>,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::MagicEden).await?;
         let seller = agent.wallet.pubkey();
 
         // Build MagicEden API request
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let mut url = format!(
-            "https://api-mainnet.magiceden.dev/v2/instructions/list?seller={}&tokenMint={}&price={}&tokenAccount={}",
-            seller,
+            "{}/instructions/list?seller={}&tokenMint={}&price={}&tokenAccount={}",
+            agent.config.base_url(Provider::MagicEden), seller,
             parsed.tokenMint,
             parsed.price,
             parsed.tokenAccount
@@ -1663,40 +1555,35 @@ impl Action for ListMagicEdenNftAction {
 
         let response = client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", parsed.magicEdenApiKey))
+            .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Ok(json!({
-                "status": "error",
-                "message": format!("MagicEden API error: {}", error_text),
-            }));
-        }
+        let response = ensure_success("Magic Eden", response).await?;
 
         let data: Value = response.json().await?;
 
         // Extract the signed transaction bytes
         let tx_signed = data.get("txSigned")
             .and_then(|t| t.get("data"))
-            .ok_or_else(|| anyhow::anyhow!("Invalid response from MagicEden API: missing txSigned.data"))?;
+            .ok_or_else(|| ActionError::malformed_response("Magic Eden", "missing txSigned.data"))?;
 
         let tx_bytes: Vec<u8> = tx_signed
             .as_array()
-            .ok_or_else(|| anyhow::anyhow!("txSigned.data is not an array"))?
+            .ok_or_else(|| ActionError::malformed_response("Magic Eden", "txSigned.data is not an array"))?
             .iter()
             .filter_map(|v| v.as_u64().map(|n| n as u8))
             .collect();
 
         // Deserialize the transaction
         let tx: VersionedTransaction = bincode::deserialize(&tx_bytes)
-            .map_err(|e| anyhow::anyhow!("Failed to deserialize transaction: {}", e))?;
+            .map_err(|e| ActionError::malformed_response("Magic Eden", format!("invalid transaction: {}", e)))?;
 
         // Sign and send
-        let signed_tx = agent.wallet.sign_transaction(tx).await?;
-        let signature = agent.client.send_and_confirm_transaction(&signed_tx)?;
+        let signature = match agent.execute_transaction(tx, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
 
         Ok(json!({
             "status": "success",
@@ -1730,16 +1617,12 @@ impl BidOnMagicEdenNftAction {
                     "type": "number",
                     "description": "Bid price in SOL",
                 },
-                "magicEdenApiKey": {
-                    "type": "string",
-                    "description": "MagicEden API key for authentication",
-                },
                 "auctionHouseAddress": {
                     "type": "string",
                     "description": "Optional auction house address",
                 }
             },
-            "required": ["tokenMint", "price", "magicEdenApiKey"],
+            "required": ["tokenMint", "price"],
             "additionalProperties": false,
         });
 
@@ -1747,7 +1630,6 @@ impl BidOnMagicEdenNftAction {
             input: json!({
                 "tokenMint": "TOKEN_MINT_ADDRESS",
                 "price": 0.5,
-                "magicEdenApiKey": "YOUR_API_KEY",
             }),
             output: json!({
                 "status": "success",
@@ -1780,7 +1662,7 @@ impl Action for BidOnMagicEdenNftAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         use solana_sdk::transaction::VersionedTransaction;
 
         #[derive(Deserialize)]
@@ -1788,18 +1670,18 @@ impl Action for BidOnMagicEdenNftAction {
         struct Input {
             tokenMint: String,
             price: f64,
-            magicEdenApiKey: String,
             auctionHouseAddress: Option<String>,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
+        let api_key = agent.api_key(Provider::MagicEden).await?;
         let buyer = agent.wallet.pubkey();
 
         // Build MagicEden API request for buy/bid
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let mut url = format!(
-            "https://api-mainnet.magiceden.dev/v2/instructions/buy?buyer={}&tokenMint={}&price={}",
-            buyer,
+            "{}/instructions/buy?buyer={}&tokenMint={}&price={}",
+            agent.config.base_url(Provider::MagicEden), buyer,
             parsed.tokenMint,
             parsed.price
         );
@@ -1810,40 +1692,35 @@ impl Action for BidOnMagicEdenNftAction {
 
         let response = client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", parsed.magicEdenApiKey))
+            .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Ok(json!({
-                "status": "error",
-                "message": format!("MagicEden API error: {}", error_text),
-            }));
-        }
+        let response = ensure_success("Magic Eden", response).await?;
 
         let data: Value = response.json().await?;
 
         // Extract the signed transaction bytes
         let tx_signed = data.get("txSigned")
             .and_then(|t| t.get("data"))
-            .ok_or_else(|| anyhow::anyhow!("Invalid response from MagicEden API: missing txSigned.data"))?;
+            .ok_or_else(|| ActionError::malformed_response("Magic Eden", "missing txSigned.data"))?;
 
         let tx_bytes: Vec<u8> = tx_signed
             .as_array()
-            .ok_or_else(|| anyhow::anyhow!("txSigned.data is not an array"))?
+            .ok_or_else(|| ActionError::malformed_response("Magic Eden", "txSigned.data is not an array"))?
             .iter()
             .filter_map(|v| v.as_u64().map(|n| n as u8))
             .collect();
 
         // Deserialize the transaction
         let tx: VersionedTransaction = bincode::deserialize(&tx_bytes)
-            .map_err(|e| anyhow::anyhow!("Failed to deserialize transaction: {}", e))?;
+            .map_err(|e| ActionError::malformed_response("Magic Eden", format!("invalid transaction: {}", e)))?;
 
         // Sign and send
-        let signed_tx = agent.wallet.sign_transaction(tx).await?;
-        let signature = agent.client.send_and_confirm_transaction(&signed_tx)?;
+        let signature = match agent.execute_transaction(tx, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
 
         Ok(json!({
             "status": "success",
@@ -0,0 +1,525 @@
+//! Spending limits and allowlists enforced before anything is signed.
+//!
+//! A [`PolicyEngine`] checks decoded transactions against a [`Policy`]. Wrap
+//! the agent's wallet in a [`PolicyWallet`] so every signature request goes
+//! through it, whichever action built the transaction, and register a
+//! [`PolicyMiddleware`] to enforce the per-action switches.
+//!
+//! Limits apply to top-level System and SPL Token instructions signed by the
+//! wallet (see [`crate::inspect`]). Funds moved through CPI, e.g. inside a
+//! swap, are not visible, so restrict `allowedPrograms` when that matters.
+//! Token instructions that cannot be decoded, such as Token-2022 extension
+//! transfers, are rejected whenever an outflow rule is configured.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{
+    compute_budget, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::error::ActionError;
+use crate::inspect::{decode_transaction, is_token_program, InstructionKind};
+use crate::jito::TIP_ACCOUNTS;
+use crate::middleware::{ActionContext, Middleware};
+use crate::wallet::Wallet;
+
+/// Rules checked before the wallet signs. Addresses are base58 strings; token
+/// amounts are in UI units (e.g. `1.5` USDC).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Policy {
+    pub max_sol_per_transaction: Option<f64>,
+    pub max_sol_per_window: Option<f64>,
+    /// Mint address → maximum amount per transaction.
+    pub max_tokens_per_transaction: HashMap<String, f64>,
+    /// Mint address → maximum amount per rolling window.
+    pub max_tokens_per_window: HashMap<String, f64>,
+    /// Length of the rolling window, in seconds. Defaults to one day.
+    pub window_secs: u64,
+    /// When set, funds and authorities may only go to these wallets (or their
+    /// associated token accounts) and to the signing wallet itself.
+    pub allowed_destinations: Option<HashSet<String>>,
+    /// Let SOL go to Jito's [`TIP_ACCOUNTS`] despite `allowed_destinations`,
+    /// so atomic bundles can pay their tip. The SOL limits still count tips.
+    pub allow_jito_tips: bool,
+    /// When set, every top-level instruction must target one of these
+    /// programs. The Compute Budget program is always allowed.
+    pub allowed_programs: Option<HashSet<String>>,
+    pub denied_mints: HashSet<String>,
+    /// Action names that may not be executed at all.
+    pub disabled_actions: HashSet<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_sol_per_transaction: None,
+            max_sol_per_window: None,
+            max_tokens_per_transaction: HashMap::new(),
+            max_tokens_per_window: HashMap::new(),
+            window_secs: 24 * 60 * 60,
+            allowed_destinations: None,
+            allow_jito_tips: true,
+            allowed_programs: None,
+            denied_mints: HashSet::new(),
+            disabled_actions: HashSet::new(),
+        }
+    }
+}
+
+impl Policy {
+    fn has_token_rules(&self) -> bool {
+        !self.denied_mints.is_empty()
+            || !self.max_tokens_per_transaction.is_empty()
+            || !self.max_tokens_per_window.is_empty()
+    }
+
+    fn has_outflow_rules(&self) -> bool {
+        self.has_token_rules()
+            || self.max_sol_per_transaction.is_some()
+            || self.max_sol_per_window.is_some()
+            || self.allowed_destinations.is_some()
+    }
+}
+
+/// Value leaving the signing wallet in one or more transactions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outflow {
+    pub lamports: u64,
+    /// Mint → UI amount.
+    pub tokens: HashMap<Pubkey, f64>,
+}
+
+impl Outflow {
+    fn add(&mut self, other: &Outflow) {
+        self.lamports = self.lamports.saturating_add(other.lamports);
+        for (mint, amount) in &other.tokens {
+            *self.tokens.entry(*mint).or_default() += amount;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Spend {
+    at: Instant,
+    outflow: Outflow,
+}
+
+#[derive(Debug)]
+pub struct PolicyEngine {
+    policy: Policy,
+    history: Mutex<VecDeque<Spend>>,
+}
+
+impl PolicyEngine {
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy,
+            history: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Reject actions listed in `disabledActions`.
+    pub fn check_action(&self, name: &str) -> Result<(), ActionError> {
+        if self.policy.disabled_actions.contains(name) {
+            return Err(violation("disabledActions", format!("action {name} is disabled")));
+        }
+        Ok(())
+    }
+
+    /// Check one transaction against the per-transaction rules and return what
+    /// it would spend. Does not touch the rolling window.
+    pub fn check_transaction(&self, signer: &Pubkey, tx: &VersionedTransaction) -> Result<Outflow, ActionError> {
+        let policy = &self.policy;
+        let keys = tx.message.static_account_keys();
+        let required = tx.message.header().num_required_signatures as usize;
+        let own_signers = &keys[..required.min(keys.len())];
+        let mut outflow = Outflow::default();
+
+        for ix in decode_transaction(tx) {
+            self.check_program(&ix.program_id)?;
+
+            match ix.kind {
+                InstructionKind::SolTransfer { from, to, lamports } if from == *signer => {
+                    outflow.lamports = outflow.lamports.saturating_add(lamports);
+                    if !(policy.allow_jito_tips && TIP_ACCOUNTS.contains(&to)) {
+                        self.check_destination(signer, own_signers, &to, None)?;
+                    }
+                }
+                // The new account has to sign the transaction, so it is ours.
+                InstructionKind::CreateAccount { from, lamports, .. } if from == *signer => {
+                    outflow.lamports = outflow.lamports.saturating_add(lamports);
+                }
+                InstructionKind::TokenTransfer {
+                    destination,
+                    authority,
+                    amount,
+                    mint,
+                    decimals,
+                    ..
+                } if authority == *signer => {
+                    let mint = self.check_mint(mint)?;
+                    if let (Some(mint), Some(decimals)) = (mint, decimals) {
+                        let ui_amount = amount as f64 / 10f64.powi(decimals as i32);
+                        *outflow.tokens.entry(mint).or_default() += ui_amount;
+                    }
+                    let token_account = mint.map(|mint| (mint, ix.program_id));
+                    self.check_destination(signer, own_signers, &destination, token_account)?;
+                }
+                InstructionKind::TokenApprove {
+                    delegate, owner, mint, ..
+                } if owner == *signer => {
+                    self.check_mint(mint)?;
+                    self.check_destination(signer, own_signers, &delegate, None)?;
+                }
+                InstructionKind::TokenSetAuthority {
+                    current_authority,
+                    new_authority: Some(new_authority),
+                    ..
+                } if current_authority == *signer => {
+                    self.check_destination(signer, own_signers, &new_authority, None)?;
+                }
+                InstructionKind::TokenCloseAccount { destination, owner, .. } if owner == *signer => {
+                    self.check_destination(signer, own_signers, &destination, None)?;
+                }
+                InstructionKind::Unresolved
+                    if policy.has_outflow_rules()
+                        && (ix.program_id == solana_sdk::system_program::id()
+                            || is_token_program(&ix.program_id)) =>
+                {
+                    return Err(violation(
+                        "addressLookupTables",
+                        format!(
+                            "cannot verify a {} instruction that uses address lookup tables",
+                            ix.program_id
+                        ),
+                    ));
+                }
+                InstructionKind::TokenUnknown { .. } if policy.has_outflow_rules() => {
+                    return Err(violation(
+                        "tokenInstructions",
+                        format!("cannot verify an undecodable {} instruction", ix.program_id),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(max) = policy.max_sol_per_transaction {
+            let sol = outflow.lamports as f64 / LAMPORTS_PER_SOL as f64;
+            if sol > max {
+                return Err(violation(
+                    "maxSolPerTransaction",
+                    format!("transaction sends {sol} SOL, limit is {max} SOL"),
+                ));
+            }
+        }
+        for (mint, amount) in &outflow.tokens {
+            if let Some(max) = policy.max_tokens_per_transaction.get(&mint.to_string()) {
+                if amount > max {
+                    return Err(violation(
+                        "maxTokensPerTransaction",
+                        format!("transaction sends {amount} of {mint}, limit is {max}"),
+                    ));
+                }
+            }
+        }
+
+        Ok(outflow)
+    }
+
+    /// Check `txs` as one batch, including the rolling-window limits, and
+    /// record their outflow if they pass.
+    pub fn authorize(&self, signer: &Pubkey, txs: &[VersionedTransaction]) -> Result<(), ActionError> {
+        let mut batch = Outflow::default();
+        for tx in txs {
+            batch.add(&self.check_transaction(signer, tx)?);
+        }
+
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let window = Duration::from_secs(self.policy.window_secs);
+        while history.front().is_some_and(|spend| spend.at.elapsed() > window) {
+            history.pop_front();
+        }
+
+        let mut total = batch.clone();
+        for spend in history.iter() {
+            total.add(&spend.outflow);
+        }
+
+        if let Some(max) = self.policy.max_sol_per_window {
+            let sol = total.lamports as f64 / LAMPORTS_PER_SOL as f64;
+            if sol > max {
+                return Err(violation(
+                    "maxSolPerWindow",
+                    format!(
+                        "{sol} SOL would be sent in the last {}s, limit is {max} SOL",
+                        self.policy.window_secs
+                    ),
+                ));
+            }
+        }
+        for (mint, amount) in &total.tokens {
+            if let Some(max) = self.policy.max_tokens_per_window.get(&mint.to_string()) {
+                if amount > max {
+                    return Err(violation(
+                        "maxTokensPerWindow",
+                        format!(
+                            "{amount} of {mint} would be sent in the last {}s, limit is {max}",
+                            self.policy.window_secs
+                        ),
+                    ));
+                }
+            }
+        }
+
+        if batch != Outflow::default() {
+            history.push_back(Spend {
+                at: Instant::now(),
+                outflow: batch,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_program(&self, program_id: &Pubkey) -> Result<(), ActionError> {
+        let Some(allowed) = &self.policy.allowed_programs else {
+            return Ok(());
+        };
+        if *program_id == compute_budget::id() || allowed.contains(&program_id.to_string()) {
+            return Ok(());
+        }
+        Err(violation(
+            "allowedPrograms",
+            format!("program {program_id} is not allowlisted"),
+        ))
+    }
+
+    /// Returns the mint back when known; fails if it is denied, or unknown
+    /// while token rules are configured.
+    fn check_mint(&self, mint: Option<Pubkey>) -> Result<Option<Pubkey>, ActionError> {
+        match mint {
+            Some(mint) if self.policy.denied_mints.contains(&mint.to_string()) => {
+                Err(violation("deniedMints", format!("mint {mint} is denied")))
+            }
+            None if self.policy.has_token_rules() => Err(violation(
+                "deniedMints",
+                "token instruction does not name its mint; use the checked variant",
+            )),
+            _ => Ok(mint),
+        }
+    }
+
+    /// `token_account` carries the mint and token program when `destination`
+    /// is a token account, so allowlisted owners' ATAs are accepted too.
+    fn check_destination(
+        &self,
+        signer: &Pubkey,
+        own_signers: &[Pubkey],
+        destination: &Pubkey,
+        token_account: Option<(Pubkey, Pubkey)>,
+    ) -> Result<(), ActionError> {
+        let Some(allowed) = &self.policy.allowed_destinations else {
+            return Ok(());
+        };
+        if own_signers.contains(destination) || allowed.contains(&destination.to_string()) {
+            return Ok(());
+        }
+        if let Some((mint, program_id)) = token_account {
+            let owners = allowed
+                .iter()
+                .filter_map(|owner| owner.parse::<Pubkey>().ok())
+                .chain(std::iter::once(*signer));
+            for owner in owners {
+                if get_associated_token_address_with_program_id(&owner, &mint, &program_id) == *destination {
+                    return Ok(());
+                }
+            }
+        }
+        Err(violation(
+            "allowedDestinations",
+            format!("destination {destination} is not allowlisted"),
+        ))
+    }
+}
+
+fn violation(rule: &str, message: impl Into<String>) -> ActionError {
+    ActionError::PolicyViolation {
+        rule: rule.to_string(),
+        message: message.into(),
+    }
+}
+
+/// A [`Wallet`] that refuses to sign anything the [`PolicyEngine`] rejects.
+#[derive(Debug)]
+pub struct PolicyWallet {
+    inner: Arc<dyn Wallet>,
+    engine: Arc<PolicyEngine>,
+}
+
+impl PolicyWallet {
+    pub fn new(inner: Arc<dyn Wallet>, engine: Arc<PolicyEngine>) -> Self {
+        Self { inner, engine }
+    }
+}
+
+#[async_trait]
+impl Wallet for PolicyWallet {
+    fn pubkey(&self) -> Pubkey {
+        self.inner.pubkey()
+    }
+
+    async fn sign_transaction(&self, tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+        self.engine.authorize(&self.pubkey(), std::slice::from_ref(&tx))?;
+        self.inner.sign_transaction(tx).await
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        txs: Vec<VersionedTransaction>,
+    ) -> anyhow::Result<Vec<VersionedTransaction>> {
+        self.engine.authorize(&self.pubkey(), &txs)?;
+        self.inner.sign_all_transactions(txs).await
+    }
+
+    /// Messages move no funds, so no spending rule applies to them.
+    async fn sign_raw_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        self.inner.sign_raw_message(message).await
+    }
+}
+
+/// Rejects actions disabled by the policy before they run.
+pub struct PolicyMiddleware {
+    engine: Arc<PolicyEngine>,
+}
+
+impl PolicyMiddleware {
+    pub fn new(engine: Arc<PolicyEngine>) -> Self {
+        Self { engine }
+    }
+}
+
+#[async_trait]
+impl Middleware for PolicyMiddleware {
+    async fn before(&self, ctx: &ActionContext<'_>, _input: &mut Value) -> Result<(), ActionError> {
+        self.engine.check_action(ctx.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, VersionedMessage},
+        system_instruction,
+    };
+    use spl_associated_token_account::get_associated_token_address;
+
+    use super::*;
+    use crate::inspect::TOKEN_2022_PROGRAM_ID;
+
+    fn tx(payer: &Pubkey, instructions: &[Instruction]) -> VersionedTransaction {
+        let message = v0::Message::try_compile(payer, instructions, &[], Hash::default()).unwrap();
+        VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::V0(message),
+        }
+    }
+
+    fn rule(err: ActionError) -> String {
+        match err {
+            ActionError::PolicyViolation { rule, .. } => rule,
+            other => panic!("expected a policy violation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn enforces_sol_limits_across_the_window() {
+        let payer = Pubkey::new_unique();
+        let engine = PolicyEngine::new(Policy {
+            max_sol_per_transaction: Some(1.0),
+            max_sol_per_window: Some(1.5),
+            ..Policy::default()
+        });
+        let send = |sol: f64| {
+            let lamports = (sol * LAMPORTS_PER_SOL as f64) as u64;
+            tx(&payer, &[system_instruction::transfer(&payer, &Pubkey::new_unique(), lamports)])
+        };
+
+        assert_eq!(rule(engine.authorize(&payer, &[send(2.0)]).unwrap_err()), "maxSolPerTransaction");
+        engine.authorize(&payer, &[send(1.0)]).unwrap();
+        assert_eq!(rule(engine.authorize(&payer, &[send(0.75)]).unwrap_err()), "maxSolPerWindow");
+        engine.authorize(&payer, &[send(0.5)]).unwrap();
+    }
+
+    #[test]
+    fn allows_associated_token_accounts_of_allowlisted_owners() {
+        let payer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let engine = PolicyEngine::new(Policy {
+            allowed_destinations: Some(HashSet::from([friend.to_string()])),
+            ..Policy::default()
+        });
+        let transfer_to = |owner: &Pubkey| {
+            let ix = spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &get_associated_token_address(&payer, &mint),
+                &mint,
+                &get_associated_token_address(owner, &mint),
+                &payer,
+                &[],
+                1,
+                0,
+            )
+            .unwrap();
+            tx(&payer, &[ix])
+        };
+
+        engine.authorize(&payer, &[transfer_to(&friend)]).unwrap();
+        let err = engine.authorize(&payer, &[transfer_to(&Pubkey::new_unique())]).unwrap_err();
+        assert_eq!(rule(err), "allowedDestinations");
+    }
+
+    #[test]
+    fn rejects_undecodable_token_2022_instructions() {
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        // Token-2022 TransferFeeExtension (26) / TransferCheckedWithFee (1):
+        // amount, decimals, fee.
+        let mut data = vec![26, 1];
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.push(6);
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            TOKEN_2022_PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(payer, true),
+            ],
+        );
+        let transfer = tx(&payer, &[ix]);
+
+        PolicyEngine::new(Policy::default()).authorize(&payer, std::slice::from_ref(&transfer)).unwrap();
+        let engine = PolicyEngine::new(Policy {
+            max_tokens_per_transaction: HashMap::from([(mint.to_string(), 1.0)]),
+            ..Policy::default()
+        });
+        assert_eq!(rule(engine.authorize(&payer, &[transfer]).unwrap_err()), "tokenInstructions");
+    }
+}
@@ -0,0 +1,182 @@
+//! Signing through a separate signing service.
+//!
+//! [`RemoteSignerWallet`] keeps no key material. It sends each transaction's
+//! serialized message to the service and accepts the returned signatures only
+//! after checking them against its public key, so a misbehaving service can
+//! make signing fail but cannot get a wrong signature into a transaction.
+//!
+//! | Route          | Request                                            | Response                          |
+//! |----------------|----------------------------------------------------|-----------------------------------|
+//! | `GET  /pubkey` |                                                    | `{ "pubkey": "<base58>" }`        |
+//! | `POST /sign`   | `{ "pubkey": "<base58>", "messages": ["<base64>"] }` | `{ "signatures": ["<base58>"] }` |
+//!
+//! Messages are bincode-serialized [`VersionedMessage`]s, the bytes a
+//! signature covers. Every request carries `Authorization: Bearer <token>`
+//! when a token is configured.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+};
+
+use crate::signing;
+use crate::wallet::Wallet;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PubkeyResponse {
+    pub pubkey: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignRequest {
+    /// The key the caller expects to sign with.
+    pub pubkey: String,
+    pub messages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignResponse {
+    /// One signature per message, in request order.
+    pub signatures: Vec<String>,
+}
+
+impl SignRequest {
+    pub fn decode_messages(&self) -> anyhow::Result<Vec<VersionedMessage>> {
+        self.messages
+            .iter()
+            .map(|message| {
+                let bytes = BASE64.decode(message).context("Message is not valid base64")?;
+                bincode::deserialize(&bytes).context("Message is not a serialized VersionedMessage")
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct RemoteSignerWallet {
+    http: reqwest::Client,
+    /// The service root; routes are appended to it.
+    endpoint: String,
+    token: Option<String>,
+    pubkey: Pubkey,
+}
+
+impl RemoteSignerWallet {
+    /// A wallet for `pubkey` on the service at `endpoint`.
+    pub fn new(endpoint: impl Into<String>, pubkey: Pubkey) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            token: None,
+            pubkey,
+        }
+    }
+
+    /// Ask the service at `endpoint` which key it signs with.
+    pub async fn connect(endpoint: impl Into<String>, token: Option<String>) -> anyhow::Result<Self> {
+        let mut wallet = Self::new(endpoint, Pubkey::default());
+        wallet.token = token;
+        let response: PubkeyResponse = wallet.send(wallet.http.get(format!("{}/pubkey", wallet.endpoint))).await?;
+        wallet.pubkey =
+            Pubkey::from_str(&response.pubkey).map_err(|e| anyhow!("Signer returned invalid pubkey: {e}"))?;
+        Ok(wallet)
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    async fn send<T: for<'de> Deserialize<'de>>(&self, request: reqwest::RequestBuilder) -> anyhow::Result<T> {
+        let request = match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await.context("Signer request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Signer returned {status}: {body}");
+        }
+        response.json().await.context("Signer returned an invalid response")
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> anyhow::Result<Vec<Signature>> {
+        let request = SignRequest {
+            pubkey: self.pubkey.to_string(),
+            messages: messages.iter().map(|message| BASE64.encode(message)).collect(),
+        };
+        let response: SignResponse = self
+            .send(self.http.post(format!("{}/sign", self.endpoint)).json(&request))
+            .await?;
+        if response.signatures.len() != messages.len() {
+            bail!(
+                "Signer returned {} signatures for {} messages",
+                response.signatures.len(),
+                messages.len()
+            );
+        }
+        response
+            .signatures
+            .iter()
+            .zip(messages)
+            .map(|(signature, message)| {
+                let signature =
+                    Signature::from_str(signature).map_err(|e| anyhow!("Signer returned invalid signature: {e}"))?;
+                if !signature.verify(self.pubkey.as_ref(), message) {
+                    bail!("Signer returned a signature that does not verify for {}", self.pubkey);
+                }
+                Ok(signature)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for RemoteSignerWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSignerWallet")
+            .field("endpoint", &self.endpoint)
+            .field("pubkey", &self.pubkey)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Wallet for RemoteSignerWallet {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    async fn sign_transaction(&self, mut tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+        signing::signer_index(&tx, &self.pubkey)?;
+        let signature = self.sign_messages(&[tx.message.serialize()]).await?.remove(0);
+        signing::add_signature(&mut tx, &self.pubkey, signature)?;
+        Ok(tx)
+    }
+
+    async fn sign_all_transactions(
+        &self,
+        mut txs: Vec<VersionedTransaction>,
+    ) -> anyhow::Result<Vec<VersionedTransaction>> {
+        for tx in &txs {
+            signing::signer_index(tx, &self.pubkey)?;
+        }
+        let messages: Vec<Vec<u8>> = txs.iter().map(|tx| tx.message.serialize()).collect();
+        let signatures = self.sign_messages(&messages).await?;
+        for (tx, signature) in txs.iter_mut().zip(signatures) {
+            signing::add_signature(tx, &self.pubkey, signature)?;
+        }
+        Ok(txs)
+    }
+}
@@ -0,0 +1,387 @@
+//! Broadcasting signed transactions until they land or their blockhash expires.
+//!
+//! `sendTransaction` only hands a transaction to the RPC node, which may drop
+//! it under load. [`send_transaction`] rebroadcasts it at a fixed interval
+//! while polling its status, and gives up only once the recent blockhash is
+//! no longer valid, i.e. once the chain has passed its `lastValidBlockHeight`
+//! and the transaction can never land.
+//!
+//! Once the transaction is out, failed polls are not fatal: the sender keeps
+//! watching until the blockhash expires or `timeout_secs` passes, and every
+//! error it returns from then on names the signature and is not retryable,
+//! so the caller looks the transaction up instead of sending it again.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::{TransactionStatus, UiTransactionEncoding};
+
+use crate::error::ActionError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SenderConfig {
+    /// Commitment the transaction must reach before it counts as sent.
+    pub commitment: CommitmentLevel,
+    /// Skip the RPC node's simulation of the first broadcast.
+    pub skip_preflight: bool,
+    /// How often the signature status is polled.
+    pub poll_interval_ms: u64,
+    /// How often the transaction is sent again while it has not landed.
+    pub rebroadcast_interval_ms: u64,
+    /// Give up watching after this long even if the RPC node never confirms
+    /// the blockhash expired, e.g. because it stopped answering.
+    pub timeout_secs: u64,
+}
+
+impl Default for SenderConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentLevel::Confirmed,
+            skip_preflight: false,
+            poll_interval_ms: 500,
+            rebroadcast_interval_ms: 2_000,
+            timeout_secs: 120,
+        }
+    }
+}
+
+/// What happened to a transaction that reached the configured commitment.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    #[serde(serialize_with = "as_string")]
+    pub signature: Signature,
+    pub slot: u64,
+    pub commitment: CommitmentLevel,
+    /// Fee in lamports; `None` if the transaction could not be fetched afterwards.
+    pub fee: Option<u64>,
+    pub compute_units_consumed: Option<u64>,
+    /// Id of the Jito bundle the transaction landed in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+}
+
+/// Send `tx` and wait until it reaches `config.commitment`.
+///
+/// Fails with [`ActionError::TransactionFailed`], carrying the program logs
+/// when the node has them, if preflight or execution fails or the blockhash
+/// expires first.
+pub(crate) async fn send_transaction(
+    client: &RpcClient,
+    config: &SenderConfig,
+    tx: &VersionedTransaction,
+) -> Result<TransactionReceipt, ActionError> {
+    let commitment = CommitmentConfig {
+        commitment: config.commitment,
+    };
+    let blockhash = *tx.message.recent_blockhash();
+    let signature = client
+        .send_transaction_with_config(
+            tx,
+            RpcSendTransactionConfig {
+                skip_preflight: config.skip_preflight,
+                preflight_commitment: Some(config.commitment),
+                encoding: Some(UiTransactionEncoding::Base64),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|err| match ActionError::from(err) {
+            ActionError::TransactionFailed { message, logs, .. } => ActionError::TransactionFailed {
+                signature: tx.signatures.first().map(ToString::to_string),
+                message,
+                logs,
+            },
+            err => err,
+        })?;
+
+    // Later broadcasts skip preflight and ask the node not to retry on its own.
+    let rebroadcast = RpcSendTransactionConfig {
+        skip_preflight: true,
+        encoding: Some(UiTransactionEncoding::Base64),
+        max_retries: Some(0),
+        ..Default::default()
+    };
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+    let mut last_sent = Instant::now();
+    loop {
+        tokio::time::sleep(Duration::from_millis(config.poll_interval_ms)).await;
+
+        // A failed poll says nothing about the transaction; try again next round.
+        let status = signature_status(client, &signature).await;
+        if let Some(status) = &status {
+            if status.err.is_some() || status.satisfies_commitment(commitment) {
+                return finish(client, config, signature, status.clone()).await;
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(ActionError::TransactionFailed {
+                signature: Some(signature.to_string()),
+                message: format!(
+                    "Could not confirm the transaction within {}s; it may still land, so look up the signature before sending again",
+                    config.timeout_secs
+                ),
+                logs: Vec::new(),
+            });
+        }
+
+        // Landed but not yet at the requested commitment; no need to resend.
+        if status.is_some() {
+            continue;
+        }
+
+        if let Ok(false) = client.is_blockhash_valid(&blockhash, CommitmentConfig::processed()).await {
+            // It may have landed between the status poll and the blockhash check.
+            if let Some(status) = signature_status(client, &signature).await {
+                if status.err.is_some() || status.satisfies_commitment(commitment) {
+                    return finish(client, config, signature, status).await;
+                }
+            }
+            return Err(ActionError::TransactionFailed {
+                signature: Some(signature.to_string()),
+                message: "Blockhash expired before the transaction was confirmed".to_string(),
+                logs: Vec::new(),
+            });
+        }
+
+        if last_sent.elapsed() >= Duration::from_millis(config.rebroadcast_interval_ms) {
+            // A failed rebroadcast is not fatal; the next status poll decides.
+            let _ = client.send_transaction_with_config(tx, rebroadcast).await;
+            last_sent = Instant::now();
+        }
+    }
+}
+
+/// The signature's status, or `None` if it is unknown or the poll failed.
+async fn signature_status(client: &RpcClient, signature: &Signature) -> Option<TransactionStatus> {
+    let mut statuses = client.get_signature_statuses(&[*signature]).await.ok()?.value;
+    statuses.pop().flatten()
+}
+
+/// Turn a final status into a receipt, or into an error with the program logs.
+async fn finish(
+    client: &RpcClient,
+    config: &SenderConfig,
+    signature: Signature,
+    status: TransactionStatus,
+) -> Result<TransactionReceipt, ActionError> {
+    // getTransaction does not serve `processed` transactions.
+    let fetch_commitment = match config.commitment {
+        CommitmentLevel::Finalized => CommitmentConfig::finalized(),
+        _ => CommitmentConfig::confirmed(),
+    };
+    let meta = client
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(fetch_commitment),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await
+        .ok()
+        .and_then(|confirmed| confirmed.transaction.meta);
+
+    if let Some(err) = status.err {
+        let logs = meta
+            .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+            .unwrap_or_default();
+        return Err(ActionError::TransactionFailed {
+            signature: Some(signature.to_string()),
+            message: err.to_string(),
+            logs,
+        });
+    }
+
+    Ok(TransactionReceipt {
+        signature,
+        slot: status.slot,
+        commitment: config.commitment,
+        fee: meta.as_ref().map(|meta| meta.fee),
+        compute_units_consumed: meta.and_then(|meta| meta.compute_units_consumed.into()),
+        bundle_id: None,
+    })
+}
+
+fn as_string<S: serde::Serializer>(signature: &Signature, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{extract::State, routing::post, Json, Router};
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde_json::{json, Value};
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction,
+    };
+
+    use super::*;
+
+    /// What the mock node answers; every poll before `failing_polls` is up fails.
+    #[derive(Default)]
+    struct Node {
+        sends: usize,
+        polls: usize,
+        failing_polls: usize,
+        lands_after_polls: Option<usize>,
+        /// Reported once landed; `confirmed` when unset.
+        landed_at: Option<&'static str>,
+        blockhash_valid: bool,
+    }
+
+    type Shared = Arc<Mutex<Node>>;
+
+    async fn rpc(State(node): State<Shared>, Json(request): Json<Value>) -> Json<Value> {
+        let mut node = node.lock().unwrap();
+        let result = match request["method"].as_str().unwrap() {
+            "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+            "sendTransaction" => {
+                node.sends += 1;
+                let bytes = STANDARD.decode(request["params"][0].as_str().unwrap()).unwrap();
+                let tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+                json!(tx.signatures[0].to_string())
+            }
+            "getSignatureStatuses" => {
+                node.polls += 1;
+                if node.polls <= node.failing_polls {
+                    return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32005, "message": "node is behind" } }));
+                }
+                let status = match node.lands_after_polls {
+                    Some(polls) if node.polls > polls => json!({
+                        "slot": 7, "confirmations": 0, "err": null, "status": { "Ok": null },
+                        "confirmationStatus": node.landed_at.unwrap_or("confirmed"),
+                    }),
+                    _ => Value::Null,
+                };
+                json!({ "context": { "slot": 7 }, "value": [status] })
+            }
+            "isBlockhashValid" => {
+                if node.polls <= node.failing_polls {
+                    return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32005, "message": "node is behind" } }));
+                }
+                json!({ "context": { "slot": 7 }, "value": node.blockhash_valid })
+            }
+            "getTransaction" => json!({
+                "slot": 7,
+                "transaction": ["", "base64"],
+                "meta": {
+                    "err": null, "status": { "Ok": null }, "fee": 5000, "preBalances": [], "postBalances": [],
+                    "computeUnitsConsumed": 450,
+                },
+                "version": 0,
+            }),
+            method => panic!("unexpected method {method}"),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn spawn(node: Node) -> (RpcClient, Shared) {
+        let node = Arc::new(Mutex::new(node));
+        let app = Router::new().route("/", post(rpc)).with_state(node.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (RpcClient::new(format!("http://{addr}")), node)
+    }
+
+    fn signed_transfer() -> VersionedTransaction {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let message = v0::Message::try_compile(&payer.pubkey(), &[ix], &[], Hash::new_unique()).unwrap();
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap()
+    }
+
+    fn config() -> SenderConfig {
+        SenderConfig {
+            poll_interval_ms: 5,
+            rebroadcast_interval_ms: 0,
+            timeout_secs: 5,
+            ..SenderConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn rebroadcasts_through_failed_polls_until_confirmed() {
+        let (client, node) = spawn(Node {
+            failing_polls: 2,
+            lands_after_polls: Some(5),
+            blockhash_valid: true,
+            ..Node::default()
+        })
+        .await;
+        let tx = signed_transfer();
+
+        let receipt = send_transaction(&client, &config(), &tx).await.unwrap();
+        assert_eq!(receipt.signature, tx.signatures[0]);
+        assert_eq!(receipt.slot, 7);
+        assert_eq!(receipt.commitment, CommitmentLevel::Confirmed);
+        assert_eq!(receipt.fee, Some(5000));
+        assert_eq!(receipt.compute_units_consumed, Some(450));
+        assert!(node.lock().unwrap().sends > 1);
+    }
+
+    #[tokio::test]
+    async fn reports_expiry_with_the_signature() {
+        let (client, _node) = spawn(Node::default()).await;
+        let tx = signed_transfer();
+
+        let err = send_transaction(&client, &config(), &tx).await.unwrap_err();
+        assert!(!err.is_retryable());
+        assert!(matches!(
+            err,
+            ActionError::TransactionFailed { signature: Some(signature), .. } if signature == tx.signatures[0].to_string()
+        ));
+
+        // A node that never answers a poll again: give up at the deadline.
+        let (client, _node) = spawn(Node {
+            failing_polls: usize::MAX,
+            ..Node::default()
+        })
+        .await;
+        let config = SenderConfig {
+            timeout_secs: 0,
+            ..config()
+        };
+        let err = send_transaction(&client, &config, &tx).await.unwrap_err();
+        assert!(!err.is_retryable());
+        assert!(err.to_string().contains("may still land"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_a_transaction_stuck_below_the_commitment() {
+        let (client, node) = spawn(Node {
+            lands_after_polls: Some(0),
+            landed_at: Some("processed"),
+            blockhash_valid: true,
+            ..Node::default()
+        })
+        .await;
+        let config = SenderConfig {
+            timeout_secs: 0,
+            ..config()
+        };
+        let tx = signed_transfer();
+
+        let sent = tokio::time::timeout(Duration::from_secs(5), send_transaction(&client, &config, &tx));
+        let err = sent.await.expect("the deadline bounds the wait").unwrap_err();
+        assert!(err.to_string().contains("may still land"), "{err}");
+        assert_eq!(node.lock().unwrap().sends, 1);
+    }
+}
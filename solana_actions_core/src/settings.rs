@@ -0,0 +1,101 @@
+//! Agent settings read from a TOML file, shared by the command-line tools.
+//!
+//! ```toml
+//! rpc_url = "https://api.devnet.solana.com"
+//! keypair_path = "/home/me/.config/solana/id.json"
+//! # Or an encrypted keystore, unlocked with $SOLANA_KEYSTORE_PASSWORD:
+//! # keystore_path = "/home/me/.config/solana-actions/agent.keystore"
+//! mode = "simulate"
+//!
+//! [base_urls]
+//! jupiterQuote = "http://localhost:8080/v6"
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
+use crate::agent::Agent;
+use crate::config::{AgentConfig, Provider};
+use crate::error::ActionError;
+use crate::execution::ExecutionMode;
+use crate::keystore::KeystoreWallet;
+use crate::wallet::{KeypairWallet, Wallet};
+
+pub const KEYSTORE_PASSWORD_VAR: &str = "SOLANA_KEYSTORE_PASSWORD";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub rpc_url: String,
+    /// Solana CLI keypair file (a JSON array of 64 bytes).
+    pub keypair_path: PathBuf,
+    /// Encrypted keystore, used instead of `keypair_path` when set.
+    pub keystore_path: Option<PathBuf>,
+    pub mode: ExecutionMode,
+    /// Provider base-URL overrides, keyed like `jupiterQuote`.
+    pub base_urls: HashMap<Provider, String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            keypair_path: default_keypair_path(),
+            keystore_path: None,
+            mode: ExecutionMode::Live,
+            base_urls: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Read `path`, or use the defaults when no file is given.
+    pub fn load(path: Option<&Path>) -> Result<Self, ActionError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ActionError::internal(format!("Failed to read {}: {e}", path.display())))?;
+        toml::from_str(&contents)
+            .map_err(|e| ActionError::invalid_input(format!("Invalid settings file {}: {e}", path.display())))
+    }
+
+    pub fn agent_config(&self) -> Result<AgentConfig, ActionError> {
+        let mut builder = AgentConfig::builder();
+        for (provider, url) in &self.base_urls {
+            builder = builder.base_url(*provider, url);
+        }
+        builder.build()
+    }
+
+    /// An agent signing with the keystore at `keystore_path`, unlocked with
+    /// the password in `SOLANA_KEYSTORE_PASSWORD`, or else with the keypair
+    /// at `keypair_path`.
+    pub fn agent(&self) -> Result<Agent, ActionError> {
+        let wallet: Arc<dyn Wallet> = match &self.keystore_path {
+            Some(path) => {
+                let password = Zeroizing::new(std::env::var(KEYSTORE_PASSWORD_VAR).map_err(|_| {
+                    ActionError::invalid_input(format!("Set {KEYSTORE_PASSWORD_VAR} to unlock {}", path.display()))
+                })?);
+                let wallet = KeystoreWallet::unlock(path, &password)
+                    .map_err(|e| ActionError::invalid_input(format!("{e:#}")))?;
+                Arc::new(wallet)
+            }
+            None => Arc::new(
+                KeypairWallet::from_file(&self.keypair_path).map_err(|e| ActionError::invalid_input(e.to_string()))?,
+            ),
+        };
+        Ok(Agent::new(wallet, &self.rpc_url)
+            .with_config(self.agent_config()?)
+            .with_mode(self.mode))
+    }
+}
+
+fn default_keypair_path() -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    Path::new(&home).join(".config/solana/id.json")
+}
@@ -0,0 +1,151 @@
+//! Putting signatures where the runtime looks for them.
+//!
+//! A transaction's signatures are positional: the signature at index `i`
+//! must be by the `i`-th static account key, for each of the message's
+//! `num_required_signatures` signers. Appending instead of placing produces a
+//! transaction that fails verification, typically with partially signed
+//! transactions from APIs such as Jupiter, Solayer or Magic Eden, which arrive
+//! with placeholder or co-signer signatures already in their slots.
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature, signer::Signer, transaction::VersionedTransaction};
+
+use crate::error::ActionError;
+
+/// The slot of `pubkey` among the signers of `tx`.
+pub fn signer_index(tx: &VersionedTransaction, pubkey: &Pubkey) -> Result<usize, ActionError> {
+    let required = usize::from(tx.message.header().num_required_signatures);
+    tx.message
+        .static_account_keys()
+        .iter()
+        .take(required)
+        .position(|key| key == pubkey)
+        .ok_or_else(|| ActionError::internal(format!("{pubkey} is not a signer of this transaction")))
+}
+
+/// Pad the signature list with default signatures up to the number of
+/// required signers, keeping any signatures already present.
+pub fn pad_signatures(tx: &mut VersionedTransaction) {
+    let required = usize::from(tx.message.header().num_required_signatures);
+    if tx.signatures.len() < required {
+        tx.signatures.resize(required, Signature::default());
+    }
+}
+
+/// Put `signature` in the slot belonging to `pubkey`.
+pub fn add_signature(tx: &mut VersionedTransaction, pubkey: &Pubkey, signature: Signature) -> Result<(), ActionError> {
+    let index = signer_index(tx, pubkey)?;
+    pad_signatures(tx);
+    tx.signatures[index] = signature;
+    Ok(())
+}
+
+/// Sign `tx` with each of `signers`, each in its own slot. Signatures from
+/// other signers are left as they are.
+pub fn partial_sign(tx: &mut VersionedTransaction, signers: &[&(dyn Signer + Sync)]) -> Result<(), ActionError> {
+    let message = tx.message.serialize();
+    for signer in signers {
+        let signature = signer.try_sign_message(&message)?;
+        add_signature(tx, &signer.try_pubkey()?, signature)?;
+    }
+    Ok(())
+}
+
+/// Required signers whose slot is still empty.
+pub fn missing_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    let required = usize::from(tx.message.header().num_required_signatures);
+    tx.message
+        .static_account_keys()
+        .iter()
+        .take(required)
+        .enumerate()
+        .filter(|(i, _)| tx.signatures.get(*i).is_none_or(|signature| *signature == Signature::default()))
+        .map(|(_, key)| *key)
+        .collect()
+}
+
+/// Fail before sending a transaction that would be rejected for a missing
+/// signature.
+pub fn ensure_fully_signed(tx: &VersionedTransaction) -> Result<(), ActionError> {
+    let missing = missing_signers(tx);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let missing: Vec<String> = missing.iter().map(ToString::to_string).collect();
+    Err(ActionError::internal(format!(
+        "Transaction is missing signatures from {}",
+        missing.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, VersionedMessage},
+        signature::Keypair,
+    };
+
+    use super::*;
+    use crate::wallet::{KeypairWallet, Wallet};
+
+    /// A message signed by `payer`, then `others` in order.
+    fn message(payer: &Pubkey, others: &[Pubkey]) -> VersionedMessage {
+        let mut accounts = vec![AccountMeta::new(*payer, true)];
+        accounts.extend(others.iter().map(|key| AccountMeta::new_readonly(*key, true)));
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        VersionedMessage::V0(v0::Message::try_compile(payer, &[ix], &[], Hash::new_unique()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn places_signatures_in_signer_order() {
+        let wallet_keypair = Keypair::new();
+        let payer = wallet_keypair.pubkey();
+        let wallet = KeypairWallet::new(wallet_keypair);
+        let mint = Keypair::new();
+        let authority = Keypair::new();
+
+        // Extra signers given out of order, the wallet signing last.
+        let mut tx = VersionedTransaction {
+            signatures: vec![],
+            message: message(&payer, &[mint.pubkey(), authority.pubkey()]),
+        };
+        partial_sign(&mut tx, &[&authority, &mint]).unwrap();
+        assert_eq!(missing_signers(&tx), vec![payer]);
+        assert!(ensure_fully_signed(&tx).is_err());
+        let tx = wallet.sign_transaction(tx).await.unwrap();
+        assert_eq!(tx.signatures.len(), 3);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        ensure_fully_signed(&tx).unwrap();
+
+        // The other order, as DEPLOY_COLLECTION and MINT_NFT sign in live
+        // mode: the wallet first, then the new mint into slot 1.
+        let tx = VersionedTransaction {
+            signatures: vec![],
+            message: message(&payer, &[mint.pubkey()]),
+        };
+        let mut tx = wallet.sign_transaction(tx).await.unwrap();
+        assert_eq!(missing_signers(&tx), vec![mint.pubkey()]);
+        partial_sign(&mut tx, &[&mint]).unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        // As returned by an API: a placeholder for the wallet and the
+        // service's own co-signature.
+        let cosigner = Keypair::new();
+        let api_message = message(&cosigner.pubkey(), &[payer]);
+        let tx = VersionedTransaction {
+            signatures: vec![cosigner.sign_message(&api_message.serialize()), Signature::default()],
+            message: api_message,
+        };
+        let tx = wallet.sign_transaction(tx).await.unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        let mut tx = VersionedTransaction {
+            signatures: vec![],
+            message: message(&payer, &[]),
+        };
+        assert!(partial_sign(&mut tx, &[&mint]).is_err());
+    }
+}
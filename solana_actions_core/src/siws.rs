@@ -0,0 +1,342 @@
+//! Sign-In With Solana (SIWS) messages.
+//!
+//! The text follows the format wallets render for `signIn`, which signs the
+//! message bytes as-is rather than inside the off-chain envelope:
+//!
+//! ```text
+//! example.com wants you to sign in with your Solana account:
+//! 8x2dR8Mpzuz2YqyZyZjUbYWKSWesBo5jMx2Q9Y86udVk
+//!
+//! Sign in to Example
+//!
+//! URI: https://example.com/login
+//! Version: 1
+//! Chain ID: mainnet
+//! Nonce: 3kTq9hJ2xN8bPz4W
+//! Issued At: 2024-05-01T12:00:00.000Z
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::message::{verify_message, MessageFormat};
+use crate::wallet::Wallet;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInMessage {
+    pub domain: String,
+    pub address: String,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    /// ISO 8601 timestamps, kept as written so the text round-trips exactly.
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub not_before: Option<String>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SignInMessage {
+    /// A version 1 message for `address` signing in to `domain`.
+    pub fn new(domain: impl Into<String>, address: &Pubkey) -> Self {
+        Self {
+            domain: domain.into(),
+            address: address.to_string(),
+            version: Some("1".to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_statement(mut self, statement: impl Into<String>) -> Self {
+        self.statement = Some(statement.into());
+        self
+    }
+
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    /// `mainnet`, `devnet`, `testnet` or `localnet`.
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.chain_id = Some(chain_id.into());
+        self
+    }
+
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    pub fn with_issued_at(mut self, at: DateTime<Utc>) -> Self {
+        self.issued_at = Some(timestamp(at));
+        self
+    }
+
+    pub fn with_expiration_time(mut self, at: DateTime<Utc>) -> Self {
+        self.expiration_time = Some(timestamp(at));
+        self
+    }
+
+    pub fn with_not_before(mut self, at: DateTime<Utc>) -> Self {
+        self.not_before = Some(timestamp(at));
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn with_resource(mut self, resource: impl Into<String>) -> Self {
+        self.resources.push(resource.into());
+        self
+    }
+
+    /// The text the wallet signs.
+    pub fn text(&self) -> String {
+        let mut text = format!("{}{HEADER_SUFFIX}\n{}", self.domain, self.address);
+        if let Some(statement) = &self.statement {
+            text.push_str(&format!("\n\n{statement}"));
+        }
+        let mut fields = Vec::new();
+        for (label, value) in [
+            ("URI", &self.uri),
+            ("Version", &self.version),
+            ("Chain ID", &self.chain_id),
+            ("Nonce", &self.nonce),
+            ("Issued At", &self.issued_at),
+            ("Expiration Time", &self.expiration_time),
+            ("Not Before", &self.not_before),
+            ("Request ID", &self.request_id),
+        ] {
+            if let Some(value) = value {
+                fields.push(format!("{label}: {value}"));
+            }
+        }
+        if !self.resources.is_empty() {
+            let resources: Vec<String> = self.resources.iter().map(|r| format!("- {r}")).collect();
+            fields.push(format!("Resources:\n{}", resources.join("\n")));
+        }
+        if !fields.is_empty() {
+            text.push_str(&format!("\n\n{}", fields.join("\n")));
+        }
+        text
+    }
+
+    /// Parse text produced by [`Self::text`] or by a wallet.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut lines = text.split('\n').peekable();
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| anyhow!("Not a Sign-In With Solana message"))?;
+        let address = lines.next().ok_or_else(|| anyhow!("Sign-in message has no address"))?;
+        let mut message = Self {
+            domain: domain.to_string(),
+            address: address.to_string(),
+            ..Self::default()
+        };
+
+        if lines.next().is_some_and(|line| !line.is_empty()) {
+            bail!("Expected a blank line after the address");
+        }
+        if let Some(line) = lines.peek() {
+            if field(line).is_none() {
+                message.statement = Some(line.to_string());
+                lines.next();
+                if lines.next().is_some_and(|line| !line.is_empty()) {
+                    bail!("Expected a blank line after the statement");
+                }
+            }
+        }
+
+        while let Some(line) = lines.next() {
+            let (label, value) = field(line).ok_or_else(|| anyhow!("Unexpected line in sign-in message: {line}"))?;
+            let slot = match label {
+                "URI" => &mut message.uri,
+                "Version" => &mut message.version,
+                "Chain ID" => &mut message.chain_id,
+                "Nonce" => &mut message.nonce,
+                "Issued At" => &mut message.issued_at,
+                "Expiration Time" => &mut message.expiration_time,
+                "Not Before" => &mut message.not_before,
+                "Request ID" => &mut message.request_id,
+                _ => {
+                    while let Some(resource) = lines.next_if(|line| line.starts_with("- ")) {
+                        message.resources.push(resource[2..].to_string());
+                    }
+                    continue;
+                }
+            };
+            if slot.replace(value.to_string()).is_some() {
+                bail!("Duplicate {label} in sign-in message");
+            }
+        }
+        Ok(message)
+    }
+
+    /// Have `wallet` sign the text.
+    pub async fn sign(&self, wallet: &dyn Wallet) -> anyhow::Result<Signature> {
+        wallet.sign_raw_message(self.text().as_bytes()).await
+    }
+
+    /// Check a signed sign-in: the signature is the named address's, the
+    /// domain and nonce are the ones the server issued, and `now` is within
+    /// the message's validity window. Returns the parsed message.
+    ///
+    /// The server must accept each nonce once; that is what stops a signed
+    /// sign-in from being replayed.
+    pub fn verify(
+        text: &str,
+        signature: &Signature,
+        domain: &str,
+        nonce: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Self> {
+        Self::check(text, signature, domain, Some(nonce), now)
+    }
+
+    /// [`verify`](Self::verify) without the nonce check, for servers that
+    /// prevent replay some other way. Anyone holding a signed sign-in can
+    /// present it again until it expires.
+    pub fn verify_without_nonce(
+        text: &str,
+        signature: &Signature,
+        domain: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Self> {
+        Self::check(text, signature, domain, None, now)
+    }
+
+    fn check(
+        text: &str,
+        signature: &Signature,
+        domain: &str,
+        nonce: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Self> {
+        let message = Self::parse(text)?;
+        if message.domain != domain {
+            bail!("Sign-in is for {}, not {domain}", message.domain);
+        }
+        let address = Pubkey::from_str(&message.address).map_err(|e| anyhow!("Invalid sign-in address: {e}"))?;
+        if !verify_message(&address, text.as_bytes(), signature, MessageFormat::Raw) {
+            bail!("Signature does not match {address}");
+        }
+        if let Some(nonce) = nonce {
+            if message.nonce.as_deref() != Some(nonce) {
+                bail!("Sign-in nonce does not match");
+            }
+        }
+        if let Some(issued_at) = &message.issued_at {
+            parse_timestamp(issued_at)?;
+        }
+        if let Some(expiration_time) = &message.expiration_time {
+            if parse_timestamp(expiration_time)? <= now {
+                bail!("Sign-in expired at {expiration_time}");
+            }
+        }
+        if let Some(not_before) = &message.not_before {
+            if parse_timestamp(not_before)? > now {
+                bail!("Sign-in is not valid before {not_before}");
+            }
+        }
+        Ok(message)
+    }
+}
+
+/// A random 16-character alphanumeric nonce.
+pub fn generate_nonce() -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect()
+}
+
+fn field(line: &str) -> Option<(&str, &str)> {
+    if line == "Resources:" {
+        return Some(("Resources", ""));
+    }
+    let (label, value) = line.split_once(": ")?;
+    matches!(
+        label,
+        "URI" | "Version" | "Chain ID" | "Nonce" | "Issued At" | "Expiration Time" | "Not Before" | "Request ID"
+    )
+    .then_some((label, value))
+}
+
+/// The `Date.toISOString()` form wallets use.
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_timestamp(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .with_context(|| format!("Invalid timestamp {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::wallet::KeypairWallet;
+
+    #[tokio::test]
+    async fn builds_signs_and_verifies() {
+        let keypair = Keypair::new();
+        let address = keypair.pubkey();
+        let wallet = KeypairWallet::new(keypair);
+        let now = Utc::now();
+        let nonce = generate_nonce();
+        let message = SignInMessage::new("example.com", &address)
+            .with_statement("Sign in to Example")
+            .with_uri("https://example.com/login")
+            .with_chain_id("mainnet")
+            .with_nonce(&nonce)
+            .with_issued_at(now)
+            .with_expiration_time(now + Duration::minutes(10))
+            .with_resource("https://example.com/terms")
+            .with_resource("https://example.com/privacy");
+
+        let text = message.text();
+        assert!(text.starts_with(&format!(
+            "example.com wants you to sign in with your Solana account:\n{address}\n\nSign in to Example\n\nURI: "
+        )));
+        assert!(text.ends_with("Resources:\n- https://example.com/terms\n- https://example.com/privacy"));
+        assert_eq!(SignInMessage::parse(&text).unwrap(), message);
+        let bare = SignInMessage::new("example.com", &address);
+        assert_eq!(SignInMessage::parse(&bare.text()).unwrap(), bare);
+
+        let signature = message.sign(&wallet).await.unwrap();
+        let verified = SignInMessage::verify(&text, &signature, "example.com", &nonce, now).unwrap();
+        assert_eq!(verified.address, address.to_string());
+        SignInMessage::verify_without_nonce(&text, &signature, "example.com", now).unwrap();
+
+        assert!(SignInMessage::verify(&text, &signature, "evil.com", &nonce, now).is_err());
+        assert!(SignInMessage::verify(&text, &signature, "example.com", "other", now).is_err());
+        assert!(SignInMessage::verify_without_nonce(&text, &signature, "example.com", now + Duration::hours(1)).is_err());
+        let tampered = text.replace("Sign in to Example", "Sign in to Exampl3");
+        assert!(SignInMessage::verify(&tampered, &signature, "example.com", &nonce, now).is_err());
+        // An envelope signature is not a sign-in signature.
+        let enveloped = wallet.sign_message(text.as_bytes()).await.unwrap();
+        assert!(SignInMessage::verify(&text, &enveloped, "example.com", &nonce, now).is_err());
+
+        // A message without a nonce only passes the explicit opt-out.
+        let signature = bare.sign(&wallet).await.unwrap();
+        assert!(SignInMessage::verify(&bare.text(), &signature, "example.com", &nonce, now).is_err());
+        SignInMessage::verify_without_nonce(&bare.text(), &signature, "example.com", now).unwrap();
+    }
+}
@@ -1,13 +1,18 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use anyhow::Result;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
 
-use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry};
+use crate::actions::{Action, ActionExample, ActionMetadata, ActionRegistry, ActionResult};
 use crate::agent::Agent;
+use crate::config::Provider;
+use crate::error::{ensure_success, ActionError};
+use crate::execution::TransactionOutcome;
+use crate::message::{verify_message, MessageFormat};
 use solana_actions_token::TokenActions;
 
 // =============================================================================
@@ -80,7 +85,7 @@ impl Action for GetBalanceAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             #[serde(default)]
@@ -172,7 +177,7 @@ impl Action for TokenBalancesAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             walletAddress: Option<String>,
@@ -186,11 +191,11 @@ impl Action for TokenBalancesAction {
             agent.wallet.pubkey()
         };
 
-        let lamports = agent.client.get_balance(&wallet_pubkey)?;
+        let lamports = agent.client.get_balance(&wallet_pubkey).await?;
         let sol_balance = lamports as f64 / 1_000_000_000.0;
 
         let rpc_url = agent.client.url();
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
 
         let request = json!({
             "jsonrpc": "2.0",
@@ -209,6 +214,7 @@ impl Action for TokenBalancesAction {
             .json(&request)
             .send()
             .await?;
+        let response = ensure_success("RPC", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -319,7 +325,7 @@ impl Action for TransferAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             to: String,
@@ -336,8 +342,12 @@ impl Action for TransferAction {
             None
         };
 
-        let signature = agent.transfer(to_pubkey, parsed.amount, mint_pubkey).await?;
-        Ok(json!({ "signature": signature }))
+        let tx = agent.build_transfer_transaction(to_pubkey, parsed.amount, mint_pubkey).await?;
+        let signature = match agent.execute_transaction(tx, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
+        Ok(json!({ "signature": signature.to_string() }))
     }
 }
 
@@ -391,7 +401,7 @@ impl Action for WalletAddressAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, _input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
         let address = agent.wallet.pubkey().to_string();
         Ok(json!({
             "status": "success",
@@ -401,6 +411,191 @@ impl Action for WalletAddressAction {
     }
 }
 
+// =============================================================================
+// SIGN_MESSAGE - Sign an off-chain message
+// =============================================================================
+
+#[derive(Debug)]
+pub struct SignMessageAction {
+    meta: ActionMetadata,
+}
+
+impl SignMessageAction {
+    pub fn new() -> Self {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Text to sign",
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["offchain", "raw"],
+                    "description": "offchain (default) wraps the text in the Solana off-chain message envelope; raw signs it as-is, as marketplace logins and Sign-In With Solana expect",
+                },
+            },
+            "required": ["message"],
+            "additionalProperties": false,
+        });
+
+        let examples = vec![ActionExample {
+            input: json!({ "message": "Log in to Tensor: 8f1c2e", "format": "raw" }),
+            output: json!({
+                "status": "success",
+                "signer": "8x2dR8Mpzuz2YqyZyZjUbYWKSWesBo5jMx2Q9Y86udVk",
+                "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+                "format": "raw",
+            }),
+            explanation: "Sign a marketplace login challenge".to_string(),
+        }];
+
+        let meta = ActionMetadata {
+            name: "SIGN_MESSAGE".to_string(),
+            similes: vec![
+                "sign message".to_string(),
+                "sign text".to_string(),
+                "prove wallet ownership".to_string(),
+            ],
+            description: "Sign an off-chain message with the agent's wallet. The signature cannot be used as a transaction signature.".to_string(),
+            examples,
+            input_schema,
+        };
+
+        Self { meta }
+    }
+}
+
+impl Default for SignMessageAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for SignMessageAction {
+    fn metadata(&self) -> &ActionMetadata {
+        &self.meta
+    }
+
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
+        #[derive(Deserialize)]
+        struct Input {
+            message: String,
+            #[serde(default)]
+            format: MessageFormat,
+        }
+
+        let parsed: Input = serde_json::from_value(input)?;
+        let signature = match parsed.format {
+            MessageFormat::Offchain => agent.wallet.sign_message(parsed.message.as_bytes()).await?,
+            MessageFormat::Raw => agent.wallet.sign_raw_message(parsed.message.as_bytes()).await?,
+        };
+        Ok(json!({
+            "status": "success",
+            "signer": agent.wallet.pubkey().to_string(),
+            "signature": signature.to_string(),
+            "format": parsed.format,
+        }))
+    }
+}
+
+// =============================================================================
+// VERIFY_SIGNATURE - Verify an off-chain message signature
+// =============================================================================
+
+#[derive(Debug)]
+pub struct VerifySignatureAction {
+    meta: ActionMetadata,
+}
+
+impl VerifySignatureAction {
+    pub fn new() -> Self {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "The signed text",
+                },
+                "signature": {
+                    "type": "string",
+                    "description": "Base58 signature",
+                },
+                "signer": {
+                    "type": "string",
+                    "description": "Address expected to have signed",
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["offchain", "raw"],
+                    "description": "How the message was signed; defaults to offchain",
+                },
+            },
+            "required": ["message", "signature", "signer"],
+            "additionalProperties": false,
+        });
+
+        let examples = vec![ActionExample {
+            input: json!({
+                "message": "Log in to Tensor: 8f1c2e",
+                "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+                "signer": "8x2dR8Mpzuz2YqyZyZjUbYWKSWesBo5jMx2Q9Y86udVk",
+                "format": "raw",
+            }),
+            output: json!({ "status": "success", "valid": true }),
+            explanation: "Check that an address signed a login challenge".to_string(),
+        }];
+
+        let meta = ActionMetadata {
+            name: "VERIFY_SIGNATURE".to_string(),
+            similes: vec![
+                "verify signature".to_string(),
+                "check signed message".to_string(),
+                "verify wallet ownership".to_string(),
+            ],
+            description: "Check whether an address signed an off-chain message.".to_string(),
+            examples,
+            input_schema,
+        };
+
+        Self { meta }
+    }
+}
+
+impl Default for VerifySignatureAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Action for VerifySignatureAction {
+    fn metadata(&self) -> &ActionMetadata {
+        &self.meta
+    }
+
+    async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
+        #[derive(Deserialize)]
+        struct Input {
+            message: String,
+            signature: String,
+            signer: String,
+            #[serde(default)]
+            format: MessageFormat,
+        }
+
+        let parsed: Input = serde_json::from_value(input)?;
+        let signer = Pubkey::from_str(&parsed.signer)?;
+        let signature = Signature::from_str(&parsed.signature)
+            .map_err(|e| ActionError::invalid_input(format!("Invalid signature: {e}")))?;
+        Ok(json!({
+            "status": "success",
+            "valid": verify_message(&signer, parsed.message.as_bytes(), &signature, parsed.format),
+        }))
+    }
+}
+
 // =============================================================================
 // GET_TPS - Get network TPS
 // =============================================================================
@@ -452,14 +647,11 @@ impl Action for GetTpsAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, _input: Value) -> Result<Value> {
-        let perf_samples = agent.client.get_recent_performance_samples(Some(1))?;
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
+        let perf_samples = agent.client.get_recent_performance_samples(Some(1)).await?;
 
         if perf_samples.is_empty() {
-            return Ok(json!({
-                "status": "error",
-                "message": "No performance samples available",
-            }));
+            return Err(ActionError::rpc("No performance samples available"));
         }
 
         let sample = &perf_samples[0];
@@ -528,20 +720,22 @@ impl Action for RequestFundsAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, _input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, _input: Value) -> ActionResult {
         use solana_sdk::native_token::LAMPORTS_PER_SOL;
 
         let pubkey = agent.wallet.pubkey();
         let signature = agent
             .client
-            .request_airdrop(&pubkey, 5 * LAMPORTS_PER_SOL)?;
+            .request_airdrop(&pubkey, 5 * LAMPORTS_PER_SOL)
+            .await?;
 
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        agent.client.confirm_transaction_with_spinner(
-            &signature,
-            &latest_blockhash,
-            solana_sdk::commitment_config::CommitmentConfig::confirmed(),
-        )?;
+        agent
+            .client
+            .poll_for_signature_with_commitment(
+                &signature,
+                solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+            )
+            .await?;
 
         Ok(json!({
             "status": "success",
@@ -610,7 +804,7 @@ impl Action for FetchPriceAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenAddress: String,
@@ -619,19 +813,13 @@ impl Action for FetchPriceAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://api.jup.ag/price/v2?ids={}",
-            parsed.tokenAddress
+            "{}/price/v2?ids={}",
+            agent.config.base_url(Provider::JupiterApi), parsed.tokenAddress
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch price: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Jupiter", response).await?;
 
         let data: Value = response.json().await?;
         let price = data["data"][&parsed.tokenAddress]["price"]
@@ -649,10 +837,11 @@ impl Action for FetchPriceAction {
         };
 
         if price_str == "N/A" {
-            return Ok(json!({
-                "status": "error",
-                "message": "Price data not available for the given token",
-            }));
+            return Err(ActionError::upstream(
+                "Jupiter",
+                None,
+                format!("Price data not available for {}", parsed.tokenAddress),
+            ));
         }
 
         Ok(json!({
@@ -737,9 +926,7 @@ impl Action for TradeAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
-        use solana_sdk::transaction::VersionedTransaction;
-
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             outputMint: String,
@@ -764,12 +951,14 @@ impl Action for TradeAction {
         let scaled_amount = (parsed.inputAmount * 10f64.powi(decimals)) as u64;
 
         let quote_url = format!(
-            "https://quote-api.jup.ag/v6/quote?inputMint={}&outputMint={}&amount={}&dynamicSlippage=true",
-            input_mint, parsed.outputMint, scaled_amount
+            "{}/quote?inputMint={}&outputMint={}&amount={}&dynamicSlippage=true",
+            agent.config.base_url(Provider::JupiterQuote), input_mint, parsed.outputMint, scaled_amount
         );
 
-        let client = reqwest::Client::new();
-        let quote_response: Value = client.get(&quote_url).send().await?.json().await?;
+        let client = &agent.config.http;
+        let response = client.get(&quote_url).send().await?;
+        let response = ensure_success("Jupiter", response).await?;
+        let quote_response: Value = response.json().await?;
 
         let swap_request = json!({
             "quoteResponse": quote_response,
@@ -779,27 +968,25 @@ impl Action for TradeAction {
             "dynamicSlippage": true,
         });
 
-        let swap_response: Value = client
-            .post("https://quote-api.jup.ag/v6/swap")
+        let response = client
+            .post(format!("{}/swap", agent.config.base_url(Provider::JupiterQuote)))
             .header("Content-Type", "application/json")
             .json(&swap_request)
             .send()
-            .await?
-            .json()
             .await?;
+        let response = ensure_success("Jupiter", response).await?;
+        let swap_response: Value = response.json().await?;
 
         let swap_tx_b64 = swap_response["swapTransaction"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("No swapTransaction in response"))?;
+            .ok_or_else(|| ActionError::malformed_response("Jupiter", "missing swapTransaction"))?;
 
-        let tx_bytes = base64_decode(swap_tx_b64)?;
-        let mut transaction: VersionedTransaction = bincode::deserialize(&tx_bytes)?;
+        let transaction = decode_transaction("Jupiter", swap_tx_b64)?;
 
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        transaction.message.set_recent_blockhash(latest_blockhash);
-
-        let signed_tx = agent.wallet.sign_transaction(transaction).await?;
-        let signature = agent.client.send_and_confirm_transaction(&signed_tx)?;
+        let signature = match agent.execute_transaction(transaction, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
 
         Ok(json!({
             "status": "success",
@@ -809,9 +996,14 @@ impl Action for TradeAction {
     }
 }
 
-fn base64_decode(input: &str) -> Result<Vec<u8>> {
+/// Decode a base64-encoded transaction returned by `provider`.
+fn decode_transaction(provider: &str, input: &str) -> Result<VersionedTransaction, ActionError> {
     use base64::{Engine as _, engine::general_purpose::STANDARD};
-    Ok(STANDARD.decode(input)?)
+    let bytes = STANDARD
+        .decode(input)
+        .map_err(|e| ActionError::malformed_response(provider, format!("invalid base64 transaction: {e}")))?;
+    bincode::deserialize(&bytes)
+        .map_err(|e| ActionError::malformed_response(provider, format!("invalid transaction: {e}")))
 }
 
 // =============================================================================
@@ -867,7 +1059,7 @@ impl Action for GetJupiterTokenListAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tags: Option<String>,
@@ -876,23 +1068,17 @@ impl Action for GetJupiterTokenListAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = match parsed.tags.as_deref() {
-            Some("strict") => "https://token.jup.ag/strict",
-            _ => "https://token.jup.ag/all",
+            Some("strict") => format!("{}/strict", agent.config.base_url(Provider::JupiterTokens)),
+            _ => format!("{}/all", agent.config.base_url(Provider::JupiterTokens)),
         };
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .get(url)
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Jupiter API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Jupiter", response).await?;
 
         let data: Value = response.json().await?;
 
@@ -958,7 +1144,7 @@ impl Action for SearchJupiterTokensAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             query: String,
@@ -967,19 +1153,13 @@ impl Action for SearchJupiterTokensAction {
         let parsed: Input = serde_json::from_value(input)?;
         let query_lower = parsed.query.to_lowercase();
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
-            .get("https://token.jup.ag/all")
+            .get(format!("{}/all", agent.config.base_url(Provider::JupiterTokens)))
             .header("Accept", "application/json")
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Jupiter API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Jupiter", response).await?;
 
         let tokens: Vec<Value> = response.json().await?;
 
@@ -1056,7 +1236,7 @@ impl Action for RugcheckAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             mint: String,
@@ -1065,19 +1245,13 @@ impl Action for RugcheckAction {
         let parsed: Input = serde_json::from_value(input)?;
 
         let url = format!(
-            "https://api.rugcheck.xyz/v1/tokens/{}/report/summary",
-            parsed.mint
+            "{}/tokens/{}/report/summary",
+            agent.config.base_url(Provider::Rugcheck), parsed.mint
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Failed to fetch rugcheck report: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Rugcheck", response).await?;
 
         let report: Value = response.json().await?;
 
@@ -1143,30 +1317,33 @@ impl Action for PythFetchPriceAction {
         &self.meta
     }
 
-    async fn call(&self, _agent: &Agent, input: Value) -> Result<Value> {
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             tokenSymbol: String,
         }
 
         let parsed: Input = serde_json::from_value(input)?;
-        let hermes_url = "https://hermes.pyth.network";
+        let hermes_url = agent.config.base_url(Provider::Pyth);
 
         let feed_url = format!(
             "{}/v2/price_feeds?query={}&asset_type=crypto",
             hermes_url, parsed.tokenSymbol
         );
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let feed_response = client.get(&feed_url).send().await?;
+        let feed_response = ensure_success("Pyth", feed_response).await?;
         let feeds: Value = feed_response.json().await?;
 
-        let feed_array = feeds.as_array().ok_or_else(|| anyhow::anyhow!("Invalid response"))?;
+        let feed_array = feeds
+            .as_array()
+            .ok_or_else(|| ActionError::malformed_response("Pyth", "price_feeds is not an array"))?;
         if feed_array.is_empty() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("No price feed found for {}", parsed.tokenSymbol),
-            }));
+            return Err(ActionError::invalid_input(format!(
+                "No price feed found for {}",
+                parsed.tokenSymbol
+            )));
         }
 
         let feed_id = feed_array
@@ -1179,7 +1356,7 @@ impl Action for PythFetchPriceAction {
             })
             .or_else(|| feed_array.first())
             .and_then(|f| f["id"].as_str())
-            .ok_or_else(|| anyhow::anyhow!("No feed ID found"))?;
+            .ok_or_else(|| ActionError::malformed_response("Pyth", "price feed has no id"))?;
 
         let price_url = format!(
             "{}/v2/updates/price/latest?ids[]={}",
@@ -1187,12 +1364,13 @@ impl Action for PythFetchPriceAction {
         );
 
         let price_response = client.get(&price_url).send().await?;
+        let price_response = ensure_success("Pyth", price_response).await?;
         let price_data: Value = price_response.json().await?;
 
         let parsed_data = price_data["parsed"]
             .as_array()
             .and_then(|a| a.first())
-            .ok_or_else(|| anyhow::anyhow!("No price data"))?;
+            .ok_or_else(|| ActionError::malformed_response("Pyth", "no parsed price data"))?;
 
         let price = parsed_data["price"]["price"]
             .as_str()
@@ -1283,9 +1461,7 @@ impl Action for CreateLimitOrderAction {
         &self.meta
     }
 
-    async fn call(&self, agent: &Agent, input: Value) -> Result<Value> {
-        use solana_sdk::transaction::VersionedTransaction;
-
+    async fn call(&self, agent: &Agent, input: Value) -> ActionResult {
         #[derive(Deserialize)]
         struct Input {
             inputMint: String,
@@ -1296,7 +1472,7 @@ impl Action for CreateLimitOrderAction {
 
         let parsed: Input = serde_json::from_value(input)?;
 
-        let url = "https://api.jup.ag/limit/v2/createOrder";
+        let url = format!("{}/limit/v2/createOrder", agent.config.base_url(Provider::JupiterApi));
 
         let order_params = json!({
             "maker": agent.wallet.pubkey().to_string(),
@@ -1309,34 +1485,26 @@ impl Action for CreateLimitOrderAction {
             }
         });
 
-        let client = reqwest::Client::new();
+        let client = &agent.config.http;
         let response = client
             .post(url)
             .header("Content-Type", "application/json")
             .json(&order_params)
             .send()
             .await?;
-
-        if !response.status().is_success() {
-            return Ok(json!({
-                "status": "error",
-                "message": format!("Jupiter API error: {}", response.status()),
-            }));
-        }
+        let response = ensure_success("Jupiter", response).await?;
 
         let data: Value = response.json().await?;
         let tx_b64 = data["tx"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("No transaction in response"))?;
+            .ok_or_else(|| ActionError::malformed_response("Jupiter", "missing tx"))?;
 
-        let tx_bytes = base64_decode(tx_b64)?;
-        let mut transaction: VersionedTransaction = bincode::deserialize(&tx_bytes)?;
+        let transaction = decode_transaction("Jupiter", tx_b64)?;
 
-        let latest_blockhash = agent.client.get_latest_blockhash()?;
-        transaction.message.set_recent_blockhash(latest_blockhash);
-
-        let signed_tx = agent.wallet.sign_transaction(transaction).await?;
-        let signature = agent.client.send_and_confirm_transaction(&signed_tx)?;
+        let signature = match agent.execute_transaction(transaction, &[]).await? {
+            TransactionOutcome::Sent(receipt) => receipt.signature,
+            preview => return Ok(preview.to_json()),
+        };
 
         Ok(json!({
             "status": "success",
@@ -1354,6 +1522,8 @@ pub fn register_token_actions(registry: &mut ActionRegistry) {
     registry.register(TokenBalancesAction::new());
     registry.register(TransferAction::new());
     registry.register(WalletAddressAction::new());
+    registry.register(SignMessageAction::new());
+    registry.register(VerifySignatureAction::new());
     registry.register(GetTpsAction::new());
     registry.register(RequestFundsAction::new());
     registry.register(FetchPriceAction::new());
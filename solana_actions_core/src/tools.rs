@@ -0,0 +1,439 @@
+//! Tool-calling definitions for LLM providers.
+//!
+//! A [`ToolSet`] snapshots the actions of an [`ActionRegistry`] and renders
+//! them as OpenAI `tools`, Anthropic `tools` or Gemini function declarations.
+//! Tool names are sanitized to what every provider accepts, so the set also
+//! maps them back: [`ToolSet::handle`] takes a tool call exactly as the
+//! provider returned it, runs the action through
+//! [`ActionRegistry::execute`] and returns the tool-result message to send back.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::actions::{ActionMetadata, ActionRegistry};
+use crate::agent::Agent;
+use crate::error::ActionError;
+
+/// Longest tool name accepted by all supported providers.
+pub const MAX_TOOL_NAME_LEN: usize = 64;
+/// OpenAI rejects function descriptions longer than this.
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+
+/// Schema keywords Gemini's OpenAPI subset understands; everything else is dropped.
+const GEMINI_SCHEMA_KEYS: &[&str] = &[
+    "type",
+    "format",
+    "description",
+    "nullable",
+    "enum",
+    "properties",
+    "required",
+    "items",
+    "minItems",
+    "maxItems",
+    "minimum",
+    "maximum",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    /// Sanitized name shown to the model.
+    pub name: String,
+    /// Name of the action in the registry.
+    pub action: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+impl ToolDefinition {
+    fn new(meta: &ActionMetadata, name: String) -> Self {
+        Self {
+            name,
+            action: meta.name.clone(),
+            description: describe(meta),
+            parameters: meta.input_schema.clone(),
+        }
+    }
+}
+
+/// A tool call extracted from a provider response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    /// Call id to echo in the result; Gemini calls have none.
+    pub id: Option<String>,
+    pub name: String,
+    pub arguments: Value,
+}
+
+impl ToolCall {
+    /// Accepts an OpenAI `tool_calls[]` entry, an Anthropic `tool_use` block,
+    /// or a Gemini part holding a `functionCall`.
+    pub fn parse(format: ToolFormat, call: &Value) -> Result<Self, ActionError> {
+        let invalid = |what: &str| ActionError::invalid_input(format!("Malformed {format:?} tool call: {what}"));
+        match format {
+            ToolFormat::OpenAi => {
+                let function = &call["function"];
+                // Arguments arrive as a JSON-encoded string.
+                let arguments = match &function["arguments"] {
+                    Value::String(raw) if raw.trim().is_empty() => json!({}),
+                    Value::String(raw) => serde_json::from_str(raw)?,
+                    Value::Null => json!({}),
+                    other => other.clone(),
+                };
+                Ok(Self {
+                    id: call["id"].as_str().map(str::to_string),
+                    name: function["name"].as_str().ok_or_else(|| invalid("missing function.name"))?.to_string(),
+                    arguments,
+                })
+            }
+            ToolFormat::Anthropic => Ok(Self {
+                id: call["id"].as_str().map(str::to_string),
+                name: call["name"].as_str().ok_or_else(|| invalid("missing name"))?.to_string(),
+                arguments: call.get("input").cloned().unwrap_or_else(|| json!({})),
+            }),
+            ToolFormat::Gemini => {
+                let function = call.get("functionCall").unwrap_or(call);
+                Ok(Self {
+                    id: None,
+                    name: function["name"].as_str().ok_or_else(|| invalid("missing name"))?.to_string(),
+                    arguments: function.get("args").cloned().unwrap_or_else(|| json!({})),
+                })
+            }
+        }
+    }
+}
+
+/// The registry's actions as provider tools, sorted by name.
+#[derive(Debug, Clone)]
+pub struct ToolSet {
+    tools: Vec<ToolDefinition>,
+    actions: HashMap<String, String>,
+}
+
+impl ToolSet {
+    pub fn new(registry: &ActionRegistry) -> Self {
+        let mut metadata = registry.metadata();
+        metadata.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Names that are already valid keep them; only the sanitized ones
+        // take a suffix when they collide.
+        let mut actions: HashMap<String, String> = metadata
+            .iter()
+            .filter(|meta| sanitize_name(&meta.name) == meta.name)
+            .map(|meta| (meta.name.clone(), meta.name.clone()))
+            .collect();
+        let mut tools = Vec::with_capacity(metadata.len());
+        for meta in &metadata {
+            let name = if actions.get(&meta.name) == Some(&meta.name) {
+                meta.name.clone()
+            } else {
+                let name = unique_name(&sanitize_name(&meta.name), &actions);
+                actions.insert(name.clone(), meta.name.clone());
+                name
+            };
+            tools.push(ToolDefinition::new(meta, name));
+        }
+        Self { tools, actions }
+    }
+
+    pub fn tools(&self) -> &[ToolDefinition] {
+        &self.tools
+    }
+
+    /// Registry name of the action behind `tool_name`.
+    pub fn action_name(&self, tool_name: &str) -> Option<&str> {
+        self.actions.get(tool_name).map(String::as_str)
+    }
+
+    pub fn export(&self, format: ToolFormat) -> Value {
+        match format {
+            ToolFormat::OpenAi => self.openai(),
+            ToolFormat::Anthropic => self.anthropic(),
+            ToolFormat::Gemini => self.gemini(),
+        }
+    }
+
+    /// The `tools` array of a Chat Completions request.
+    pub fn openai(&self) -> Value {
+        Value::Array(
+            self.tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.parameters,
+                        },
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// The `tools` array of a Messages API request.
+    pub fn anthropic(&self) -> Value {
+        Value::Array(
+            self.tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "input_schema": tool.parameters,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// One entry of a Gemini request's `tools` array.
+    pub fn gemini(&self) -> Value {
+        let declarations: Vec<Value> = self
+            .tools
+            .iter()
+            .map(|tool| {
+                let mut declaration = json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                });
+                // Gemini rejects an empty `properties` object.
+                let parameters = gemini_schema(&tool.parameters);
+                if parameters["properties"].as_object().is_some_and(|p| !p.is_empty()) {
+                    declaration["parameters"] = parameters;
+                }
+                declaration
+            })
+            .collect();
+        json!({ "functionDeclarations": declarations })
+    }
+
+    /// Run a tool call returned by the model and build the message carrying
+    /// its result. Action failures are reported to the model as error
+    /// results; only a call that cannot be parsed fails.
+    pub async fn handle(
+        &self,
+        format: ToolFormat,
+        registry: &ActionRegistry,
+        agent: &Agent,
+        call: &Value,
+    ) -> Result<Value, ActionError> {
+        let call = ToolCall::parse(format, call)?;
+        let result = match self.action_name(&call.name) {
+            Some(action) => registry.execute(action, agent, call.arguments.clone()).await,
+            None => Err(ActionError::UnknownAction {
+                name: call.name.clone(),
+            }),
+        };
+        let (content, is_error) = match result {
+            Ok(output) => (output, false),
+            Err(err) => (err.to_json(), true),
+        };
+        Ok(tool_result(format, &call, content, is_error))
+    }
+}
+
+/// The message that returns `content` for `call` to the model.
+pub fn tool_result(format: ToolFormat, call: &ToolCall, content: Value, is_error: bool) -> Value {
+    match format {
+        ToolFormat::OpenAi => json!({
+            "role": "tool",
+            "tool_call_id": call.id,
+            "content": content.to_string(),
+        }),
+        ToolFormat::Anthropic => json!({
+            "type": "tool_result",
+            "tool_use_id": call.id,
+            "content": content.to_string(),
+            "is_error": is_error,
+        }),
+        ToolFormat::Gemini => json!({
+            "functionResponse": {
+                "name": call.name,
+                "response": if is_error { json!({ "error": content }) } else { json!({ "output": content }) },
+            },
+        }),
+    }
+}
+
+/// Replace characters outside `[A-Za-z0-9_-]`, make sure the name starts
+/// with a letter or `_`, and cap its length.
+pub fn sanitize_name(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    out.truncate(MAX_TOOL_NAME_LEN);
+    out
+}
+
+fn unique_name(name: &str, taken: &HashMap<String, String>) -> String {
+    if !taken.contains_key(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!("_{n}");
+            let mut base = name.to_string();
+            base.truncate(MAX_TOOL_NAME_LEN - suffix.len());
+            base + &suffix
+        })
+        .find(|candidate| !taken.contains_key(candidate))
+        .expect("an unused suffix exists")
+}
+
+/// Description followed by the similes and example inputs, cut to
+/// [`MAX_DESCRIPTION_LEN`] characters.
+fn describe(meta: &ActionMetadata) -> String {
+    let mut description = meta.description.trim().to_string();
+    if !meta.similes.is_empty() {
+        description.push_str(&format!("\nAlso known as: {}.", meta.similes.join(", ")));
+    }
+    for example in &meta.examples {
+        description.push_str(&format!("\nExample: {} ({})", example.input, example.explanation));
+    }
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        description = description.chars().take(MAX_DESCRIPTION_LEN - 3).collect::<String>() + "...";
+    }
+    description
+}
+
+fn gemini_schema(schema: &Value) -> Value {
+    let Some(object) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut out = Map::new();
+    for (key, value) in object {
+        if !GEMINI_SCHEMA_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match key.as_str() {
+            "properties" => Value::Object(
+                value
+                    .as_object()
+                    .map(|props| props.iter().map(|(k, v)| (k.clone(), gemini_schema(v))).collect())
+                    .unwrap_or_default(),
+            ),
+            "items" => gemini_schema(value),
+            // Gemini takes a single type; `["string", "null"]` becomes
+            // `"string"` with `nullable: true`.
+            "type" => match value.as_array() {
+                Some(types) => {
+                    if types.iter().any(|t| t == "null") {
+                        out.insert("nullable".to_string(), Value::Bool(true));
+                    }
+                    types.iter().find(|t| *t != "null").cloned().unwrap_or(Value::Null)
+                }
+                None => value.clone(),
+            },
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), value);
+    }
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use solana_sdk::signature::Keypair;
+
+    use super::*;
+    use crate::actions::{Action, ActionExample, ActionResult};
+    use crate::token_actions::TransferAction;
+    use crate::wallet::KeypairWallet;
+
+    struct Echo(ActionMetadata);
+
+    #[async_trait]
+    impl Action for Echo {
+        fn metadata(&self) -> &ActionMetadata {
+            &self.0
+        }
+
+        async fn call(&self, _agent: &Agent, input: Value) -> ActionResult {
+            Ok(json!({ "status": "success", "echo": input["text"] }))
+        }
+    }
+
+    fn echo(name: &str) -> Echo {
+        Echo(ActionMetadata {
+            name: name.to_string(),
+            similes: vec!["repeat".to_string()],
+            description: "Echo the text back".to_string(),
+            examples: vec![ActionExample {
+                input: json!({ "text": "hi" }),
+                output: json!({ "echo": "hi" }),
+                explanation: "Echo a greeting".to_string(),
+            }],
+            input_schema: json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"],
+                "additionalProperties": false,
+            }),
+        })
+    }
+
+    #[tokio::test]
+    async fn exports_and_routes_tool_calls() {
+        let mut registry = ActionRegistry::new();
+        registry.register(echo("ECHO.TEXT"));
+        registry.register(echo("ECHO_TEXT"));
+        let tools = ToolSet::new(&registry);
+
+        assert_eq!(tools.action_name("ECHO_TEXT"), Some("ECHO_TEXT"));
+        assert_eq!(tools.action_name("ECHO_TEXT_2"), Some("ECHO.TEXT"));
+        let description = tools.openai()[0]["function"]["description"].as_str().unwrap().to_string();
+        assert!(description.contains("Also known as: repeat."));
+        assert!(description.contains(r#"Example: {"text":"hi"} (Echo a greeting)"#));
+        assert_eq!(tools.anthropic()[0]["input_schema"]["required"], json!(["text"]));
+        assert!(tools.gemini()["functionDeclarations"][0]["parameters"]
+            .get("additionalProperties")
+            .is_none());
+
+        let agent = Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), "http://localhost:8899");
+        let call = json!({
+            "id": "call_1",
+            "type": "function",
+            "function": { "name": "ECHO_TEXT_2", "arguments": "{\"text\":\"gm\"}" },
+        });
+        let reply = tools.handle(ToolFormat::OpenAi, &registry, &agent, &call).await.unwrap();
+        assert_eq!(reply["tool_call_id"], "call_1");
+        assert_eq!(reply["content"], json!({ "status": "success", "echo": "gm" }).to_string());
+
+        let call = json!({ "type": "tool_use", "id": "toolu_1", "name": "ECHO_TEXT", "input": {} });
+        let reply = tools.handle(ToolFormat::Anthropic, &registry, &agent, &call).await.unwrap();
+        assert_eq!(reply["is_error"], true);
+    }
+
+    #[test]
+    fn gemini_schemas_use_nullable_for_optional_types() {
+        let mut registry = ActionRegistry::new();
+        registry.register(TransferAction::new());
+        let tools = ToolSet::new(&registry);
+
+        let parameters = &tools.gemini()["functionDeclarations"][0]["parameters"];
+        assert_eq!(parameters["properties"]["mint"]["type"], "string");
+        assert_eq!(parameters["properties"]["mint"]["nullable"], true);
+        assert_eq!(parameters["properties"]["to"]["type"], "string");
+        assert!(parameters["properties"]["to"].get("nullable").is_none());
+    }
+}
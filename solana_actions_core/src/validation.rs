@@ -0,0 +1,298 @@
+//! Validation of action input against the JSON Schema published in
+//! `ActionMetadata::input_schema`.
+//!
+//! Only the subset of JSON Schema used by the bundled actions is supported:
+//! `type` (single or list), `properties`, `required`, `additionalProperties`,
+//! `items` and `enum`. Unknown keywords are ignored.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A single problem found while validating an input value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Path to the offending field, e.g. `amount` or `mints[2]`.
+    /// Problems with the input as a whole use an empty path.
+    pub field: String,
+    pub message: String,
+}
+
+/// Returned when an action's input does not match its declared schema.
+/// Callers hand it back as [`ActionError::InvalidInput`](crate::error::ActionError::InvalidInput).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub action: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid input for {}: ", self.action)?;
+        let details: Vec<String> = self
+            .errors
+            .iter()
+            .map(|e| {
+                if e.field.is_empty() {
+                    e.message.clone()
+                } else {
+                    format!("{}: {}", e.field, e.message)
+                }
+            })
+            .collect();
+        write!(f, "{}", details.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validate `input` against `schema`, collecting every mismatch rather than
+/// stopping at the first one.
+pub fn validate_input(action: &str, schema: &Value, input: &Value) -> Result<(), ValidationError> {
+    let mut errors = Vec::new();
+    validate_value(schema, input, "", &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError {
+            action: action.to_string(),
+            errors,
+        })
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<FieldError>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(t, value)) {
+            errors.push(FieldError {
+                field: path.to_string(),
+                message: format!("expected {}, got {}", allowed.join(" or "), type_name(value)),
+            });
+            // Further checks would only produce noise for a value of the wrong type.
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            let rendered: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+            errors.push(FieldError {
+                field: path.to_string(),
+                message: format!("must be one of {}", rendered.join(", ")),
+            });
+        }
+    }
+
+    match value {
+        Value::Object(fields) => validate_object(schema, fields, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{path}[{i}]"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    fields: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<FieldError>,
+) {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+
+    if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+        for name in required.iter().filter_map(|r| r.as_str()) {
+            if !fields.contains_key(name) {
+                errors.push(FieldError {
+                    field: join_path(path, name),
+                    message: "missing required field".to_string(),
+                });
+            }
+        }
+    }
+
+    for (name, value) in fields {
+        let field_path = join_path(path, name);
+        match properties.get(name) {
+            Some(property_schema) => validate_value(property_schema, value, &field_path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    let mut known: Vec<&str> = properties.keys().map(|k| k.as_str()).collect();
+                    known.sort_unstable();
+                    let message = if known.is_empty() {
+                        "unexpected field; this action takes no input".to_string()
+                    } else {
+                        format!("unexpected field; allowed fields are {}", known.join(", "))
+                    };
+                    errors.push(FieldError {
+                        field: field_path,
+                        message,
+                    });
+                }
+                Some(extra_schema @ Value::Object(_)) => {
+                    validate_value(extra_schema, value, &field_path, errors)
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => match value {
+            Value::Number(n) => n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0),
+            _ => false,
+        },
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        // Unknown type names are not ours to reject.
+        _ => true,
+    }
+}
+
+/// Convert a string argument (a command-line flag, a query parameter) to
+/// the type `property` declares. Arrays also take comma-separated items;
+/// untyped properties take the string as JSON if it parses.
+pub fn coerce_str(property: &Value, raw: &str) -> Result<Value, String> {
+    let value = match schema_type(property) {
+        Some("string") => Value::String(raw.to_string()),
+        Some("number" | "integer") => match serde_json::from_str(raw) {
+            Ok(number @ Value::Number(_)) => number,
+            _ => return Err(format!("expected a number, got `{raw}`")),
+        },
+        Some("boolean") => match raw {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => return Err(format!("expected true or false, got `{raw}`")),
+        },
+        Some("array") if !raw.trim_start().starts_with('[') => raw
+            .split(',')
+            .map(|item| coerce_str(&property["items"], item.trim()))
+            .collect::<Result<_, _>>()?,
+        Some("array" | "object") => serde_json::from_str(raw).map_err(|e| format!("expected JSON: {e}"))?,
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    };
+    Ok(value)
+}
+
+/// The declared type of `property`, skipping `null` in unions like
+/// `["string", "null"]`.
+pub fn schema_type(property: &Value) -> Option<&str> {
+    match &property["type"] {
+        Value::String(ty) => Some(ty),
+        Value::Array(types) => types.iter().filter_map(Value::as_str).find(|ty| *ty != "null"),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(parent: &str, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{parent}.{field}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn transfer_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "to": { "type": "string" },
+                "amount": { "type": "number" },
+                "mint": { "type": ["string", "null"] },
+                "mints": { "type": "array", "items": { "type": "string" } },
+                "side": { "type": "string", "enum": ["buy", "sell"] },
+            },
+            "required": ["to", "amount"],
+            "additionalProperties": false,
+        })
+    }
+
+    #[test]
+    fn accepts_valid_input() {
+        let input = json!({ "to": "abc", "amount": 1, "mint": null });
+        assert!(validate_input("TRANSFER", &transfer_schema(), &input).is_ok());
+    }
+
+    #[test]
+    fn reports_every_bad_field() {
+        let input = json!({
+            "amount": "1",
+            "mints": ["a", 2],
+            "side": "hold",
+            "memo": "hi",
+        });
+        let err = validate_input("TRANSFER", &transfer_schema(), &input).unwrap_err();
+        let fields: Vec<&str> = err.errors.iter().map(|e| e.field.as_str()).collect();
+
+        assert_eq!(err.action, "TRANSFER");
+        assert!(fields.contains(&"to"));
+        assert!(fields.contains(&"amount"));
+        assert!(fields.contains(&"mints[1]"));
+        assert!(fields.contains(&"side"));
+        assert!(fields.contains(&"memo"));
+    }
+
+    #[test]
+    fn rejects_non_object_input() {
+        let err = validate_input("TRANSFER", &transfer_schema(), &json!("send it")).unwrap_err();
+        assert_eq!(err.errors.len(), 1);
+        assert_eq!(err.errors[0].field, "");
+    }
+
+    #[test]
+    fn registered_examples_match_their_schemas() {
+        let mut registry = crate::actions::ActionRegistry::new();
+        crate::register_all_actions(&mut registry);
+        for meta in registry.metadata() {
+            for example in &meta.examples {
+                if let Err(err) = validate_input(&meta.name, &meta.input_schema, &example.input) {
+                    panic!("{err}");
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,21 @@
 use solana_sdk::{
+    derivation_path::DerivationPath,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{
+        keypair_from_seed, keypair_from_seed_and_derivation_path, read_keypair_file, Keypair, Signature, Signer,
+    },
     transaction::VersionedTransaction,
 };
+use anyhow::{anyhow, bail, Context};
 use async_trait::async_trait;
+use bip39::{Language, Mnemonic, Seed};
 use std::fmt::Debug;
+use std::path::Path;
 use std::sync::Arc;
 
+use crate::message::{ensure_not_transaction, offchain_envelope};
+use crate::signing;
+
 /// A trait for signing Solana transactions.
 #[async_trait]
 pub trait Wallet: Send + Sync + Debug {
@@ -19,6 +28,18 @@ pub trait Wallet: Send + Sync + Debug {
         &self,
         txs: Vec<VersionedTransaction>,
     ) -> anyhow::Result<Vec<VersionedTransaction>>;
+
+    /// Sign `message` inside the Solana off-chain message envelope.
+    async fn sign_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        self.sign_raw_message(&offchain_envelope(message)?).await
+    }
+
+    /// Sign `message` exactly as given, as Sign-In With Solana and
+    /// marketplace logins expect. Implementations must refuse bytes that
+    /// parse as a transaction message; see [`ensure_not_transaction`].
+    async fn sign_raw_message(&self, _message: &[u8]) -> anyhow::Result<Signature> {
+        bail!("This wallet cannot sign messages")
+    }
 }
 
 #[derive(Debug)]
@@ -32,6 +53,83 @@ impl KeypairWallet {
             keypair: Arc::new(keypair),
         }
     }
+
+    /// Load a Solana CLI keypair file (a JSON array of 64 bytes).
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let keypair = read_keypair_file(path).map_err(|e| anyhow!("Failed to read keypair {}: {e}", path.display()))?;
+        Ok(Self::new(keypair))
+    }
+
+    /// A base58 secret as exported by Phantom or Solflare: the 64-byte
+    /// keypair, or just its 32-byte secret key.
+    pub fn from_base58(secret: &str) -> anyhow::Result<Self> {
+        let bytes = bs58::decode(secret.trim())
+            .into_vec()
+            .context("Secret key is not valid base58")?;
+        keypair_from_bytes(&bytes).map(Self::new)
+    }
+
+    /// Read the secret from the environment variable `name`, either in
+    /// base58 or as a Solana CLI JSON byte array.
+    pub fn from_env(name: &str) -> anyhow::Result<Self> {
+        let value = std::env::var(name).with_context(|| format!("Environment variable {name} is not set"))?;
+        let value = value.trim();
+        if value.starts_with('[') {
+            let bytes: Vec<u8> =
+                serde_json::from_str(value).with_context(|| format!("{name} is not a JSON byte array"))?;
+            keypair_from_bytes(&bytes).map(Self::new)
+        } else {
+            Self::from_base58(value).with_context(|| format!("Invalid secret key in {name}"))
+        }
+    }
+
+    /// Account `account` of a BIP39 mnemonic, derived at
+    /// `m/44'/501'/{account}'/0'` as Phantom and Solflare do. `passphrase`
+    /// is the optional BIP39 passphrase (empty for none).
+    pub fn from_mnemonic(phrase: &str, passphrase: &str, account: u32) -> anyhow::Result<Self> {
+        let seed = mnemonic_seed(phrase, passphrase)?;
+        derive_keypair(seed.as_bytes(), DerivationPath::new_bip44(Some(account), Some(0))).map(Self::new)
+    }
+
+    /// A BIP39 mnemonic derived at an explicit hardened path such as
+    /// `m/44'/501'/0'` (Solana CLI `?key=0`) or `m/44'/501'/3'/0'`.
+    pub fn from_mnemonic_path(phrase: &str, passphrase: &str, path: &str) -> anyhow::Result<Self> {
+        let path = DerivationPath::from_absolute_path_str(path)
+            .map_err(|e| anyhow!("Invalid derivation path {path}: {e}"))?;
+        let seed = mnemonic_seed(phrase, passphrase)?;
+        derive_keypair(seed.as_bytes(), path).map(Self::new)
+    }
+
+    /// Public keys of the first `count` accounts of a mnemonic, in the order
+    /// wallets list them, to find the index to pass to [`Self::from_mnemonic`].
+    pub fn mnemonic_accounts(phrase: &str, passphrase: &str, count: u32) -> anyhow::Result<Vec<Pubkey>> {
+        let seed = mnemonic_seed(phrase, passphrase)?;
+        (0..count)
+            .map(|account| {
+                derive_keypair(seed.as_bytes(), DerivationPath::new_bip44(Some(account), Some(0)))
+                    .map(|keypair| keypair.pubkey())
+            })
+            .collect()
+    }
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> anyhow::Result<Keypair> {
+    match bytes.len() {
+        64 => Keypair::from_bytes(bytes).map_err(|e| anyhow!("Invalid keypair: {e}")),
+        32 => keypair_from_seed(bytes).map_err(|e| anyhow!("Invalid secret key: {e}")),
+        len => bail!("Expected a 64-byte keypair or 32-byte secret key, got {len} bytes"),
+    }
+}
+
+fn mnemonic_seed(phrase: &str, passphrase: &str) -> anyhow::Result<Seed> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = Mnemonic::from_phrase(&phrase, Language::English).map_err(|e| anyhow!("Invalid mnemonic: {e}"))?;
+    Ok(Seed::new(&mnemonic, passphrase))
+}
+
+fn derive_keypair(seed: &[u8], path: DerivationPath) -> anyhow::Result<Keypair> {
+    keypair_from_seed_and_derivation_path(seed, Some(path)).map_err(|e| anyhow!("Key derivation failed: {e}"))
 }
 
 #[async_trait]
@@ -44,9 +142,7 @@ impl Wallet for KeypairWallet {
         &self,
         mut tx: VersionedTransaction,
     ) -> anyhow::Result<VersionedTransaction> {
-        let message_bytes = tx.message.serialize();
-        let signature = self.keypair.sign_message(&message_bytes);
-        tx.signatures.push(signature);
+        signing::partial_sign(&mut tx, &[&*self.keypair])?;
         Ok(tx)
     }
 
@@ -55,10 +151,58 @@ impl Wallet for KeypairWallet {
         mut txs: Vec<VersionedTransaction>,
     ) -> anyhow::Result<Vec<VersionedTransaction>> {
         for tx in &mut txs {
-            let message_bytes = tx.message.serialize();
-            let signature = self.keypair.sign_message(&message_bytes);
-            tx.signatures.push(signature);
+            signing::partial_sign(tx, &[&*self.keypair])?;
         }
         Ok(txs)
     }
-}
\ No newline at end of file
+
+    async fn sign_raw_message(&self, message: &[u8]) -> anyhow::Result<Signature> {
+        ensure_not_transaction(message)?;
+        Ok(self.keypair.sign_message(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn loads_keypairs_from_every_format() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+
+        let base58 = KeypairWallet::from_base58(&keypair.to_base58_string()).unwrap();
+        assert_eq!(base58.pubkey(), pubkey);
+        let secret = bs58::encode(&keypair.to_bytes()[..32]).into_string();
+        assert_eq!(KeypairWallet::from_base58(&secret).unwrap().pubkey(), pubkey);
+        assert!(KeypairWallet::from_base58("not-base58!").is_err());
+
+        let path = std::env::temp_dir().join(format!("keypair-{pubkey}.json"));
+        solana_sdk::signature::write_keypair_file(&keypair, &path).unwrap();
+        assert_eq!(KeypairWallet::from_file(&path).unwrap().pubkey(), pubkey);
+        std::fs::remove_file(&path).unwrap();
+
+        let var = format!("WALLET_TEST_{pubkey}");
+        std::env::set_var(&var, format!("{:?}", keypair.to_bytes()));
+        assert_eq!(KeypairWallet::from_env(&var).unwrap().pubkey(), pubkey);
+        std::env::set_var(&var, keypair.to_base58_string());
+        assert_eq!(KeypairWallet::from_env(&var).unwrap().pubkey(), pubkey);
+        std::env::remove_var(&var);
+        assert!(KeypairWallet::from_env(&var).is_err());
+
+        let accounts = KeypairWallet::mnemonic_accounts(PHRASE, "", 3).unwrap();
+        assert_eq!(accounts.len(), 3);
+        // First account Phantom shows for this phrase.
+        assert_eq!(accounts[0].to_string(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert_ne!(accounts[0], accounts[1]);
+        let second = KeypairWallet::from_mnemonic(PHRASE, "", 1).unwrap();
+        assert_eq!(second.pubkey(), accounts[1]);
+        let by_path = KeypairWallet::from_mnemonic_path(PHRASE, "", "m/44'/501'/1'/0'").unwrap();
+        assert_eq!(by_path.pubkey(), accounts[1]);
+        assert_ne!(KeypairWallet::from_mnemonic(PHRASE, "secret", 1).unwrap().pubkey(), accounts[1]);
+        // "about" carries the checksum; any other last word fails it.
+        assert!(KeypairWallet::from_mnemonic(&PHRASE.replace("about", "abandon"), "", 0).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! `solana-actions-mcp`: serves every registered action as an MCP tool.
+//!
+//! Speaks stdio by default, which is what desktop clients launch; pass
+//! `--http` to serve streamable HTTP instead.
+
+mod server;
+mod transport;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use solana_actions_core::{register_all_actions, ActionRegistry, ExecutionMode, Settings};
+
+use crate::server::McpServer;
+
+#[derive(Debug, Parser)]
+#[command(name = "solana-actions-mcp", version, about)]
+struct Args {
+    /// TOML settings file.
+    #[arg(long, env = "SOLANA_ACTIONS_CONFIG")]
+    config: Option<PathBuf>,
+    /// Overrides `rpc_url` from the settings file.
+    #[arg(long, env = "SOLANA_RPC_URL")]
+    rpc_url: Option<String>,
+    /// Overrides `keypair_path` from the settings file.
+    #[arg(long, env = "SOLANA_KEYPAIR_PATH")]
+    keypair: Option<PathBuf>,
+    /// Overrides `keystore_path` from the settings file.
+    #[arg(long, env = "SOLANA_KEYSTORE_PATH")]
+    keystore: Option<PathBuf>,
+    /// Simulate or only build transactions instead of sending them.
+    #[arg(long, value_parser = parse_mode)]
+    mode: Option<ExecutionMode>,
+    /// Serve streamable HTTP on this address instead of stdio.
+    #[arg(long, value_name = "ADDR")]
+    http: Option<SocketAddr>,
+    /// Environment variable holding the bearer token HTTP callers must
+    /// present. Required unless `--http` is a loopback address.
+    #[arg(long, value_name = "VAR", default_value = "MCP_API_TOKEN")]
+    token_env: String,
+}
+
+fn parse_mode(mode: &str) -> Result<ExecutionMode, String> {
+    serde_json::from_value(serde_json::Value::String(mode.to_string()))
+        .map_err(|_| "expected live, simulate or buildOnly".to_string())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut settings = Settings::load(args.config.as_deref())?;
+    if let Some(rpc_url) = args.rpc_url {
+        settings.rpc_url = rpc_url;
+    }
+    if let Some(keypair) = args.keypair {
+        settings.keypair_path = keypair;
+    }
+    if let Some(keystore) = args.keystore {
+        settings.keystore_path = Some(keystore);
+    }
+    if let Some(mode) = args.mode {
+        settings.mode = mode;
+    }
+
+    let agent = settings.agent()?;
+    let mut registry = ActionRegistry::new();
+    register_all_actions(&mut registry);
+    blinks::register_blink_actions(&mut registry);
+    let server = Arc::new(McpServer::new(registry, agent));
+
+    match args.http {
+        Some(addr) => {
+            let token = std::env::var(&args.token_env).ok().filter(|token| !token.is_empty());
+            transport::serve_http(server, addr, token).await
+        }
+        None => transport::serve_stdio(server).await,
+    }
+}
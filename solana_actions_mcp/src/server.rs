@@ -0,0 +1,198 @@
+//! Transport-independent MCP message handling.
+//!
+//! Implements the lifecycle (`initialize`, `ping`) and the tools capability
+//! (`tools/list`, `tools/call`) of the Model Context Protocol on top of
+//! JSON-RPC 2.0. Tools are the registry's actions, named and described as in
+//! [`ToolSet`].
+
+use serde_json::{json, Value};
+use solana_actions_core::{agent::Agent, ActionRegistry, ToolSet};
+
+/// Revision answered when the client asks for one we do not know.
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+const SUPPORTED_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub struct McpServer {
+    registry: ActionRegistry,
+    agent: Agent,
+    tools: ToolSet,
+}
+
+impl McpServer {
+    pub fn new(registry: ActionRegistry, agent: Agent) -> Self {
+        let tools = ToolSet::new(&registry);
+        Self {
+            registry,
+            agent,
+            tools,
+        }
+    }
+
+    /// Handle one raw message, which may be a batch. Returns `None` when
+    /// nothing is to be sent back (notifications and responses).
+    pub async fn handle_text(&self, text: &str) -> Option<Value> {
+        match serde_json::from_str(text) {
+            Ok(message) => self.handle(message).await,
+            Err(e) => Some(error(Value::Null, PARSE_ERROR, format!("Parse error: {e}"))),
+        }
+    }
+
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let Value::Array(batch) = message else {
+            return self.handle_one(message).await;
+        };
+        if batch.is_empty() {
+            return Some(error(Value::Null, INVALID_REQUEST, "Empty batch"));
+        }
+        let mut responses = Vec::new();
+        for message in batch {
+            responses.extend(self.handle_one(message).await);
+        }
+        (!responses.is_empty()).then_some(Value::Array(responses))
+    }
+
+    async fn handle_one(&self, message: Value) -> Option<Value> {
+        let Some(method) = message["method"].as_str() else {
+            // A response to a server request; we never send any.
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error(message["id"].clone(), INVALID_REQUEST, "Missing method"));
+        };
+        // Notifications carry no id and get no response.
+        let id = message.get("id")?.clone();
+        let params = &message["params"];
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools_list() })),
+            "tools/call" => self.tools_call(params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error(id, code, message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION);
+        let version = if SUPPORTED_VERSIONS.contains(&requested) {
+            requested
+        } else {
+            PROTOCOL_VERSION
+        };
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": {
+                "name": "solana-actions",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": format!(
+                "Tools act on the Solana wallet {}. Transactions are executed in {:?} mode.",
+                self.agent.wallet.pubkey(),
+                self.agent.mode,
+            ),
+        })
+    }
+
+    fn tools_list(&self) -> Vec<Value> {
+        self.tools
+            .tools()
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": tool.parameters,
+                })
+            })
+            .collect()
+    }
+
+    async fn tools_call(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params["name"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let action = self
+            .tools
+            .action_name(name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {name}")))?;
+        let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        // Action failures are tool results the model can read, not protocol errors.
+        let (output, is_error) = match self.registry.execute(action, &self.agent, arguments).await {
+            Ok(output) => (output, false),
+            Err(err) => (err.to_json(), true),
+        };
+        let mut result = json!({
+            "content": [{ "type": "text", "text": output.to_string() }],
+            "isError": is_error,
+        });
+        if output.is_object() {
+            result["structuredContent"] = output;
+        }
+        Ok(result)
+    }
+}
+
+fn error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_actions_core::{register_all_actions, wallet::KeypairWallet};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn lists_and_calls_tools() {
+        let keypair = Keypair::new();
+        let address = keypair.pubkey().to_string();
+        let agent = Agent::new(Arc::new(KeypairWallet::new(keypair)), "http://localhost:8899");
+        let mut registry = ActionRegistry::new();
+        register_all_actions(&mut registry);
+        let server = McpServer::new(registry, agent);
+
+        let init = server
+            .handle_text(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert!(server
+            .handle_text(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let batch = server
+            .handle(json!([
+                { "jsonrpc": "2.0", "id": 2, "method": "tools/list" },
+                { "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": { "name": "WALLET_ADDRESS", "arguments": {} } },
+                { "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": { "name": "NOPE" } },
+            ]))
+            .await
+            .unwrap();
+        let tools = batch[0]["result"]["tools"].as_array().unwrap();
+        assert!(tools
+            .iter()
+            .any(|tool| tool["name"] == "TRANSFER" && tool["inputSchema"]["type"] == "object"));
+        assert_eq!(batch[1]["result"]["isError"], false);
+        assert_eq!(batch[1]["result"]["structuredContent"]["address"], address);
+        assert_eq!(batch[2]["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,136 @@
+//! The stdio and streamable HTTP transports.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::server::McpServer;
+
+/// Newline-delimited JSON-RPC on stdin and stdout, until stdin closes.
+pub async fn serve_stdio(server: Arc<McpServer>) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_text(&line).await {
+            stdout.write_all(format!("{response}\n").as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+struct HttpState {
+    server: Arc<McpServer>,
+    /// Bearer token callers must present; `None` accepts every caller.
+    token: Option<String>,
+}
+
+/// Streamable HTTP at `/mcp`. Every POST is answered with a single JSON
+/// body; the server never opens an SSE stream, so GET is not allowed.
+///
+/// Without a `token` anyone who can reach the port can call every action
+/// with the server's wallet, so only loopback addresses are accepted then.
+pub async fn serve_http(server: Arc<McpServer>, addr: SocketAddr, token: Option<String>) -> Result<()> {
+    if token.is_none() && !addr.ip().is_loopback() {
+        bail!("Refusing to serve {addr} without a bearer token; set one or bind to a loopback address");
+    }
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("MCP server listening on http://{}/mcp", listener.local_addr()?);
+    axum::serve(listener, router(server, token)).await?;
+    Ok(())
+}
+
+fn router(server: Arc<McpServer>, token: Option<String>) -> Router {
+    Router::new()
+        .route("/mcp", post(handle_post).get(method_not_allowed).delete(method_not_allowed))
+        .with_state(Arc::new(HttpState { server, token }))
+}
+
+async fn handle_post(State(state): State<Arc<HttpState>>, headers: HeaderMap, body: String) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response();
+    }
+    // Browsers send an Origin; reject foreign ones to stop DNS rebinding.
+    if let Some(origin) = headers.get(header::ORIGIN).and_then(|o| o.to_str().ok()) {
+        if !is_local_origin(origin) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+    match state.server.handle_text(&body).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+async fn method_not_allowed() -> StatusCode {
+    StatusCode::METHOD_NOT_ALLOWED
+}
+
+fn authorized(expected: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = match host.rfind(':') {
+        Some(i) if !host.ends_with(']') => &host[..i],
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_actions_core::{agent::Agent, wallet::KeypairWallet, ActionRegistry};
+    use solana_sdk::signature::Keypair;
+
+    use super::*;
+
+    fn server() -> Arc<McpServer> {
+        let agent = Agent::new(Arc::new(KeypairWallet::new(Keypair::new())), "http://localhost:8899");
+        Arc::new(McpServer::new(ActionRegistry::new(), agent))
+    }
+
+    #[tokio::test]
+    async fn requires_a_token_off_loopback() {
+        let err = serve_http(server(), "0.0.0.0:0".parse().unwrap(), None).await.unwrap_err();
+        assert!(err.to_string().contains("without a bearer token"), "{err}");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let app = router(server(), Some("secret".to_string()));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let ping = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let client = reqwest::Client::new();
+        let response = client.post(&url).body(ping).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = client.post(&url).bearer_auth("wrong").body(ping).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = client.post(&url).bearer_auth("secret").body(ping).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
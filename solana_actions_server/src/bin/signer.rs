@@ -0,0 +1,41 @@
+//! `solana-actions-signer`: the reference remote signer, holding one keypair
+//! for `RemoteSignerWallet` clients.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use solana_actions_core::wallet::KeypairWallet;
+use solana_actions_server::signer::{serve_signer, SignerServer};
+
+#[derive(Debug, Parser)]
+#[command(name = "solana-actions-signer", version, about)]
+struct Args {
+    /// Solana CLI keypair file to sign with.
+    #[arg(long, env = "SOLANA_KEYPAIR_PATH")]
+    keypair: PathBuf,
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8900")]
+    bind: SocketAddr,
+    /// Environment variable holding the bearer token callers must present.
+    #[arg(long, value_name = "VAR", default_value = "SIGNER_API_TOKEN")]
+    token_env: String,
+    /// Accept requests without a token. Only for local testing; refused
+    /// unless `--bind` is a loopback address.
+    #[arg(long)]
+    no_auth: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut server = SignerServer::new(KeypairWallet::from_file(&args.keypair)?);
+    if !args.no_auth {
+        let token = std::env::var(&args.token_env)
+            .ok()
+            .filter(|token| !token.is_empty())
+            .with_context(|| format!("Set {} or pass --no-auth", args.token_env))?;
+        server = server.with_token(token);
+    }
+    serve_signer(server, args.bind).await
+}
@@ -0,0 +1,94 @@
+//! Server settings read from a TOML file.
+//!
+//! ```toml
+//! bind = "127.0.0.1:8080"
+//! timeout_secs = 60
+//!
+//! [[tenants]]
+//! name = "payments"
+//! token_env = "PAYMENTS_API_TOKEN"
+//! rpc_url = "https://api.devnet.solana.com"
+//! keypair_path = "/etc/solana-actions/payments.json"
+//! mode = "simulate"
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use solana_actions_core::{ActionRegistry, ExecutionMode, Provider, Settings};
+
+use crate::{ActionServer, DEFAULT_TIMEOUT};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerSettings {
+    #[serde(default = "default_bind")]
+    pub bind: SocketAddr,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Provider base-URL overrides shared by every tenant.
+    #[serde(default)]
+    pub base_urls: HashMap<Provider, String>,
+    pub tenants: Vec<TenantSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantSettings {
+    pub name: String,
+    /// The bearer token itself. Prefer `token_env` outside development.
+    pub token: Option<String>,
+    /// Environment variable holding the bearer token.
+    pub token_env: Option<String>,
+    pub rpc_url: String,
+    pub keypair_path: PathBuf,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+}
+
+impl ServerSettings {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid settings file {}", path.display()))
+    }
+
+    /// A server with one agent per tenant.
+    pub fn server(&self, registry: ActionRegistry) -> Result<ActionServer> {
+        let mut server = ActionServer::new(registry).with_timeout(Duration::from_secs(self.timeout_secs));
+        for tenant in &self.tenants {
+            let token = match (&tenant.token, &tenant.token_env) {
+                (Some(token), None) => token.clone(),
+                (None, Some(var)) => std::env::var(var)
+                    .with_context(|| format!("Tenant {}: {var} is not set", tenant.name))?,
+                _ => bail!("Tenant {}: set exactly one of token and token_env", tenant.name),
+            };
+            if token.is_empty() {
+                bail!("Tenant {}: empty token", tenant.name);
+            }
+            let settings = Settings {
+                rpc_url: tenant.rpc_url.clone(),
+                keypair_path: tenant.keypair_path.clone(),
+                keystore_path: None,
+                mode: tenant.mode,
+                base_urls: self.base_urls.clone(),
+            };
+            let agent = settings
+                .agent()
+                .with_context(|| format!("Tenant {}", tenant.name))?;
+            server = server.with_tenant(token, &tenant.name, agent);
+        }
+        Ok(server)
+    }
+}
+
+fn default_bind() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 8080))
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT.as_secs()
+}
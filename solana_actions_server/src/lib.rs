@@ -0,0 +1,351 @@
+//! HTTP access to an [`ActionRegistry`] for services not written in Rust.
+//!
+//! | Route                              | Body                       |
+//! |------------------------------------|----------------------------|
+//! | `GET  /v1/actions`                 | `{ "actions": [metadata] }` |
+//! | `GET  /v1/actions/{name}`          | one action's metadata       |
+//! | `POST /v1/actions/{name}/execute`  | the action's output         |
+//! | `POST /v1/rpc`                     | JSON-RPC 2.0, see [`rpc`]   |
+//!
+//! Every request carries `Authorization: Bearer <token>`. The token selects a
+//! tenant, and the tenant's own [`Agent`] (wallet, RPC URL, mode) runs the
+//! action. Failures are [`ActionError::to_json`] bodies with a matching
+//! HTTP status.
+
+pub mod config;
+pub mod rpc;
+pub mod signer;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use solana_actions_core::{agent::Agent, ActionError, ActionRegistry, ActionResult};
+
+pub use config::ServerSettings;
+pub use signer::SignerServer;
+
+/// Applied when [`ActionServer::with_timeout`] is not called.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// One caller of the server and the agent its requests run against.
+pub struct Tenant {
+    pub name: String,
+    pub agent: Agent,
+}
+
+pub struct ActionServer {
+    registry: ActionRegistry,
+    /// `(bearer token, tenant)` pairs.
+    tenants: Vec<(String, Arc<Tenant>)>,
+    timeout: Duration,
+}
+
+impl ActionServer {
+    pub fn new(registry: ActionRegistry) -> Self {
+        Self {
+            registry,
+            tenants: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Serve requests bearing `token` with `agent`.
+    pub fn with_tenant(mut self, token: impl Into<String>, name: impl Into<String>, agent: Agent) -> Self {
+        let tenant = Tenant {
+            name: name.into(),
+            agent,
+        };
+        self.tenants.push((token.into(), Arc::new(tenant)));
+        self
+    }
+
+    /// Upper bound on a single action execution.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn registry(&self) -> &ActionRegistry {
+        &self.registry
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/v1/actions", get(list_actions))
+            .route("/v1/actions/:name", get(get_action))
+            .route("/v1/actions/:name/execute", post(execute_action))
+            .route("/v1/rpc", post(rpc::handle_rpc))
+            .with_state(Arc::new(self))
+    }
+
+    /// The tenant whose token is in the `Authorization` header.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Arc<Tenant>, ActionError> {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ActionError::unauthorized(None, "Missing bearer token"))?;
+        // Compare against every token so timing does not reveal a prefix match.
+        let mut found = None;
+        for (candidate, tenant) in &self.tenants {
+            if constant_time_eq(candidate.as_bytes(), token.as_bytes()) {
+                found = Some(tenant.clone());
+            }
+        }
+        found.ok_or_else(|| ActionError::unauthorized(None, "Invalid bearer token"))
+    }
+
+    /// Run `name` for `tenant`, failing with [`ActionError::Timeout`] once
+    /// the configured timeout passes.
+    pub async fn execute(&self, tenant: &Tenant, name: &str, input: Value) -> ActionResult {
+        match tokio::time::timeout(self.timeout, self.registry.execute(name, &tenant.agent, input)).await {
+            Ok(result) => result,
+            Err(_) => Err(ActionError::Timeout {
+                timeout_ms: self.timeout.as_millis() as u64,
+            }),
+        }
+    }
+}
+
+/// Serve `server` on `addr` until the process exits.
+pub async fn serve(server: ActionServer, addr: SocketAddr) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("Action server listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, server.router()).await?;
+    Ok(())
+}
+
+/// HTTP status for an action failure.
+pub fn status_code(err: &ActionError) -> StatusCode {
+    match err {
+        ActionError::InvalidInput { .. } => StatusCode::BAD_REQUEST,
+        ActionError::UnknownAction { .. } => StatusCode::NOT_FOUND,
+        ActionError::Upstream { .. } | ActionError::Rpc { .. } => StatusCode::BAD_GATEWAY,
+        ActionError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        ActionError::TransactionFailed { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        ActionError::NotImplemented { .. } => StatusCode::NOT_IMPLEMENTED,
+        ActionError::Unauthorized { .. } | ActionError::PolicyViolation { .. } => StatusCode::FORBIDDEN,
+        ActionError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+        ActionError::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn error_response(err: &ActionError) -> Response {
+    (status_code(err), Json(err.to_json())).into_response()
+}
+
+/// 401 rather than the 403 an action's own `Unauthorized` maps to.
+fn auth_error_response(err: &ActionError) -> Response {
+    let mut response = (StatusCode::UNAUTHORIZED, Json(err.to_json())).into_response();
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn list_actions(State(server): State<Arc<ActionServer>>, headers: HeaderMap) -> Response {
+    if let Err(err) = server.authenticate(&headers) {
+        return auth_error_response(&err);
+    }
+    let mut actions = server.registry.metadata();
+    actions.sort_by(|a, b| a.name.cmp(&b.name));
+    Json(json!({ "actions": actions })).into_response()
+}
+
+async fn get_action(State(server): State<Arc<ActionServer>>, headers: HeaderMap, Path(name): Path<String>) -> Response {
+    if let Err(err) = server.authenticate(&headers) {
+        return auth_error_response(&err);
+    }
+    match server.registry.get(&name) {
+        Some(action) => Json(action.metadata().clone()).into_response(),
+        None => error_response(&ActionError::UnknownAction { name }),
+    }
+}
+
+async fn execute_action(
+    State(server): State<Arc<ActionServer>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    body: String,
+) -> Response {
+    let tenant = match server.authenticate(&headers) {
+        Ok(tenant) => tenant,
+        Err(err) => return auth_error_response(&err),
+    };
+    // An empty body is the same as `{}`.
+    let input = if body.trim().is_empty() {
+        json!({})
+    } else {
+        match serde_json::from_str(&body) {
+            Ok(input) => input,
+            Err(e) => return error_response(&ActionError::invalid_input(format!("Body is not valid JSON: {e}"))),
+        }
+    };
+    match server.execute(&tenant, &name, input).await {
+        Ok(output) => Json(output).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use solana_actions_core::{
+        actions::{Action, ActionMetadata},
+        register_all_actions,
+        wallet::Wallet,
+    };
+    use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+    use super::*;
+
+    /// Hands transactions back unsigned; nothing here reaches a chain.
+    #[derive(Debug)]
+    struct MockWallet(Pubkey);
+
+    #[async_trait]
+    impl Wallet for MockWallet {
+        fn pubkey(&self) -> Pubkey {
+            self.0
+        }
+
+        async fn sign_transaction(&self, tx: VersionedTransaction) -> anyhow::Result<VersionedTransaction> {
+            Ok(tx)
+        }
+
+        async fn sign_all_transactions(
+            &self,
+            txs: Vec<VersionedTransaction>,
+        ) -> anyhow::Result<Vec<VersionedTransaction>> {
+            Ok(txs)
+        }
+    }
+
+    struct Sleep(ActionMetadata);
+
+    #[async_trait]
+    impl Action for Sleep {
+        fn metadata(&self) -> &ActionMetadata {
+            &self.0
+        }
+
+        async fn call(&self, _agent: &Agent, _input: Value) -> ActionResult {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(json!({}))
+        }
+    }
+
+    fn agent(wallet: Pubkey) -> Agent {
+        Agent::new(Arc::new(MockWallet(wallet)), "http://localhost:8899")
+    }
+
+    #[tokio::test]
+    async fn routes_tenants_and_maps_errors() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut registry = ActionRegistry::new();
+        register_all_actions(&mut registry);
+        registry.register(Sleep(ActionMetadata {
+            name: "SLEEP".to_string(),
+            similes: Vec::new(),
+            description: "Sleeps".to_string(),
+            examples: Vec::new(),
+            input_schema: json!({ "type": "object" }),
+        }));
+        let server = ActionServer::new(registry)
+            .with_tenant("alice-token", "alice", agent(alice))
+            .with_tenant("bob-token", "bob", agent(bob))
+            .with_timeout(Duration::from_millis(50));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, server.router()).await });
+        let http = reqwest::Client::new();
+        let call = |method: reqwest::Method, path: &str, token: &str| {
+            http.request(method, format!("{base}{path}")).bearer_auth(token)
+        };
+
+        let response = http.get(format!("{base}/v1/actions")).send().await.unwrap();
+        assert_eq!(response.status(), 401);
+        let response = call(reqwest::Method::GET, "/v1/actions", "wrong").send().await.unwrap();
+        assert_eq!(response.status(), 401);
+
+        let list: Value = call(reqwest::Method::GET, "/v1/actions", "alice-token")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(list["actions"].as_array().unwrap().iter().any(|a| a["name"] == "TRANSFER"));
+        let schema: Value = call(reqwest::Method::GET, "/v1/actions/TRANSFER", "alice-token")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(schema["input_schema"]["type"], "object");
+
+        for (token, wallet) in [("alice-token", alice), ("bob-token", bob)] {
+            let output: Value = call(reqwest::Method::POST, "/v1/actions/WALLET_ADDRESS/execute", token)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(output["address"], wallet.to_string());
+        }
+
+        let response = call(reqwest::Method::POST, "/v1/actions/TRANSFER/execute", "alice-token")
+            .json(&json!({ "amount": "lots" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+        assert_eq!(response.json::<Value>().await.unwrap()["code"], "INVALID_INPUT");
+        let response = call(reqwest::Method::POST, "/v1/actions/NOPE/execute", "alice-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+        let response = call(reqwest::Method::POST, "/v1/actions/SLEEP/execute", "alice-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 504);
+        assert_eq!(response.json::<Value>().await.unwrap()["code"], "TIMEOUT");
+
+        let rpc: Value = call(reqwest::Method::POST, "/v1/rpc", "bob-token")
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "execute", "params": { "name": "WALLET_ADDRESS" } }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(rpc["result"]["address"], bob.to_string());
+        let rpc: Value = call(reqwest::Method::POST, "/v1/rpc", "bob-token")
+            .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "getAction", "params": { "name": "NOPE" } }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(rpc["error"]["data"]["code"], "UNKNOWN_ACTION");
+    }
+}
@@ -0,0 +1,36 @@
+//! `solana-actions-server`: the REST and JSON-RPC server over every
+//! registered action.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use solana_actions_core::{register_all_actions, ActionRegistry};
+use solana_actions_server::ServerSettings;
+
+#[derive(Debug, Parser)]
+#[command(name = "solana-actions-server", version, about)]
+struct Args {
+    /// TOML server settings with one `[[tenants]]` entry per caller.
+    #[arg(long, env = "SOLANA_ACTIONS_SERVER_CONFIG")]
+    config: PathBuf,
+    /// Overrides `bind` from the settings file.
+    #[arg(long, value_name = "ADDR")]
+    bind: Option<SocketAddr>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut settings = ServerSettings::load(&args.config)?;
+    if let Some(bind) = args.bind {
+        settings.bind = bind;
+    }
+
+    let mut registry = ActionRegistry::new();
+    register_all_actions(&mut registry);
+    blinks::register_blink_actions(&mut registry);
+    let server = settings.server(registry)?;
+    solana_actions_server::serve(server, settings.bind).await
+}
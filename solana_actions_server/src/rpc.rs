@@ -0,0 +1,86 @@
+//! JSON-RPC 2.0 at `POST /v1/rpc`, for clients that prefer one endpoint.
+//!
+//! | Method        | Params                | Result                      |
+//! |---------------|-----------------------|-----------------------------|
+//! | `listActions` | none                  | `{ "actions": [metadata] }` |
+//! | `getAction`   | `{ name }`            | one action's metadata       |
+//! | `execute`     | `{ name, input? }`    | the action's output         |
+//!
+//! Action failures are error code `-32000` (`-32602` for bad input or an
+//! unknown action) with [`ActionError::to_json`] as `data`.
+
+use std::sync::Arc;
+
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, response::Response, Json};
+use serde_json::{json, Value};
+use solana_actions_core::ActionError;
+
+use crate::{auth_error_response, ActionServer};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const ACTION_ERROR: i64 = -32000;
+
+pub(crate) async fn handle_rpc(State(server): State<Arc<ActionServer>>, headers: HeaderMap, body: String) -> Response {
+    let tenant = match server.authenticate(&headers) {
+        Ok(tenant) => tenant,
+        Err(err) => return auth_error_response(&err),
+    };
+    let request: Value = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(e) => return Json(error(Value::Null, PARSE_ERROR, format!("Parse error: {e}"), None)).into_response(),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request["method"].as_str() else {
+        return Json(error(id, INVALID_REQUEST, "Missing method".to_string(), None)).into_response();
+    };
+    let params = &request["params"];
+
+    let result = match method {
+        "listActions" => {
+            let mut actions = server.registry().metadata();
+            actions.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(json!({ "actions": actions }))
+        }
+        "getAction" | "execute" => {
+            let Some(name) = params["name"].as_str() else {
+                return Json(error(id, INVALID_PARAMS, "Missing action name".to_string(), None)).into_response();
+            };
+            if method == "getAction" {
+                server
+                    .registry()
+                    .get(name)
+                    .map(|action| json!(action.metadata()))
+                    .ok_or_else(|| ActionError::UnknownAction { name: name.to_string() })
+            } else {
+                let input = params.get("input").cloned().unwrap_or_else(|| json!({}));
+                server.execute(&tenant, name, input).await
+            }
+        }
+        _ => {
+            return Json(error(id, METHOD_NOT_FOUND, format!("Method not found: {method}"), None)).into_response();
+        }
+    };
+
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => {
+            let code = match err {
+                ActionError::InvalidInput { .. } | ActionError::UnknownAction { .. } => INVALID_PARAMS,
+                _ => ACTION_ERROR,
+            };
+            error(id, code, err.to_string(), Some(err.to_json()))
+        }
+    };
+    Json(response).into_response()
+}
+
+fn error(id: Value, code: i64, message: String, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
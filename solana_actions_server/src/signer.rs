@@ -0,0 +1,210 @@
+//! Reference implementation of the remote signing protocol, serving one
+//! [`KeypairWallet`] to [`RemoteSignerWallet`](solana_actions_core::RemoteSignerWallet)
+//! clients.
+//!
+//! It signs any message that names its key as a signer. A production signer
+//! would apply its own policy before signing; this one exists so the client
+//! can be run and tested end to end.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use solana_actions_core::remote_signer::{PubkeyResponse, SignRequest, SignResponse};
+use solana_actions_core::wallet::{KeypairWallet, Wallet};
+use solana_sdk::signature::Signer;
+
+use crate::constant_time_eq;
+
+pub struct SignerServer {
+    wallet: KeypairWallet,
+    /// Bearer token callers must present; `None` accepts every caller and
+    /// limits [`serve_signer`] to loopback addresses.
+    token: Option<String>,
+}
+
+impl SignerServer {
+    pub fn new(wallet: KeypairWallet) -> Self {
+        Self { wallet, token: None }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/pubkey", get(pubkey))
+            .route("/sign", post(sign))
+            .with_state(Arc::new(self))
+    }
+
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = &self.token else {
+            return true;
+        };
+        headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Serve `server` on `addr` until the process exits.
+///
+/// A server without a token signs for anyone who can reach it, so it is only
+/// served on loopback addresses.
+pub async fn serve_signer(server: SignerServer, addr: SocketAddr) -> anyhow::Result<()> {
+    if server.token.is_none() && !addr.ip().is_loopback() {
+        anyhow::bail!("Refusing to serve {addr} without a bearer token; set one or bind to a loopback address");
+    }
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "Signer for {} listening on http://{}",
+        server.wallet.pubkey(),
+        listener.local_addr()?
+    );
+    axum::serve(listener, server.router()).await?;
+    Ok(())
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+async fn pubkey(State(server): State<Arc<SignerServer>>, headers: HeaderMap) -> Response {
+    if !server.authorized(&headers) {
+        return error(StatusCode::UNAUTHORIZED, "Invalid bearer token");
+    }
+    Json(PubkeyResponse {
+        pubkey: server.wallet.pubkey().to_string(),
+    })
+    .into_response()
+}
+
+async fn sign(
+    State(server): State<Arc<SignerServer>>,
+    headers: HeaderMap,
+    Json(request): Json<SignRequest>,
+) -> Response {
+    if !server.authorized(&headers) {
+        return error(StatusCode::UNAUTHORIZED, "Invalid bearer token");
+    }
+    let pubkey = server.wallet.pubkey();
+    if request.pubkey != pubkey.to_string() {
+        return error(StatusCode::FORBIDDEN, format!("This signer only holds {pubkey}"));
+    }
+    let messages = match request.decode_messages() {
+        Ok(messages) => messages,
+        Err(e) => return error(StatusCode::BAD_REQUEST, format!("{e:#}")),
+    };
+    let mut signatures = Vec::with_capacity(messages.len());
+    for message in &messages {
+        let signers = usize::from(message.header().num_required_signatures);
+        if !message.static_account_keys().iter().take(signers).any(|key| *key == pubkey) {
+            return error(StatusCode::BAD_REQUEST, format!("{pubkey} is not a signer of every message"));
+        }
+        signatures.push(server.wallet.keypair.sign_message(&message.serialize()).to_string());
+    }
+    Json(SignResponse { signatures }).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+        system_instruction,
+        transaction::VersionedTransaction,
+    };
+    use solana_actions_core::RemoteSignerWallet;
+
+    use super::*;
+
+    async fn spawn(server: SignerServer) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, server.router()).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn transfer(payer: &Pubkey, signers: &[Pubkey]) -> VersionedTransaction {
+        let mut instructions = vec![system_instruction::transfer(payer, &Pubkey::new_unique(), 1)];
+        for signer in signers {
+            instructions.push(system_instruction::transfer(signer, payer, 1));
+        }
+        let message = v0::Message::try_compile(payer, &instructions, &[], Hash::default()).unwrap();
+        VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::V0(message),
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_through_reference_server() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let url = spawn(SignerServer::new(KeypairWallet::new(keypair)).with_token("secret")).await;
+
+        assert!(RemoteSignerWallet::connect(url.as_str(), Some("wrong".to_string())).await.is_err());
+        let wallet = RemoteSignerWallet::connect(url.as_str(), Some("secret".to_string())).await.unwrap();
+        assert_eq!(wallet.pubkey(), pubkey);
+
+        let signed = wallet.sign_transaction(transfer(&pubkey, &[])).await.unwrap();
+        assert_eq!(signed.signatures.len(), 1);
+        assert!(signed.verify_with_results().iter().all(|ok| *ok));
+
+        // As a second signer, the signature lands in the wallet's own slot.
+        let payer = Keypair::new();
+        let mut tx = transfer(&payer.pubkey(), &[pubkey]);
+        tx.signatures = vec![payer.sign_message(&tx.message.serialize()), Signature::default()];
+        let signed = wallet.sign_all_transactions(vec![tx]).await.unwrap().remove(0);
+        assert!(signed.verify_with_results().iter().all(|ok| *ok));
+
+        assert!(wallet.sign_transaction(transfer(&Pubkey::new_unique(), &[])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_signatures_from_another_key() {
+        let expected = Pubkey::new_unique();
+        // A signer holding a different key, answering as if it held `expected`.
+        let impostor = Arc::new(Keypair::new());
+        let app = Router::new().route(
+            "/sign",
+            post(move |Json(request): Json<SignRequest>| async move {
+                let signatures = request
+                    .decode_messages()
+                    .unwrap()
+                    .iter()
+                    .map(|message| impostor.sign_message(&message.serialize()).to_string())
+                    .collect();
+                Json(SignResponse { signatures })
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let wallet = RemoteSignerWallet::new(format!("http://{addr}"), expected);
+        let err = wallet.sign_transaction(transfer(&expected, &[])).await.unwrap_err();
+        assert!(err.to_string().contains("does not verify"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn refuses_open_signers_off_loopback() {
+        let server = SignerServer::new(KeypairWallet::new(Keypair::new()));
+        let err = serve_signer(server, "0.0.0.0:0".parse().unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("without a bearer token"), "{err}");
+    }
+}